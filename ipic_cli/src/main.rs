@@ -1,8 +1,12 @@
 use ipic_parse::{self, create_parse_stream};
 
 fn main() {
-    let parser = create_parse_stream("fn main() { let a = 10 + 10; 20; }");
-    for expr in parser {
+    let (nodes, diagnostics) = create_parse_stream("fn main() { let a = 10 + 10; 20; }");
+    for expr in nodes {
         println!("{:?}", expr);
     }
+
+    for diagnostic in diagnostics {
+        eprintln!("{}", diagnostic);
+    }
 }
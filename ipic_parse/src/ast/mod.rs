@@ -1,11 +1,101 @@
-use ipic_tokenize::grammar::TokenKind;
+use ipic_symbol::Symbol;
+use ipic_tokenize::grammar::{NumSuffix, TokenKind};
+use ipic_tokenize::span::Span;
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-pub enum Node {
-    INTEGER(i32),
+#[derive(Clone, PartialEq, PartialOrd)]
+pub struct Node {
+    pub kind: NodeKind,
+    pub span: Span
+}
+
+impl Node {
+    pub fn new(kind: NodeKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+impl Node {
+    // whether the expression can be evaluated at compile time, names are not resolved
+    // here so they are taken to be globals and have to be checked by a later pass
+    pub fn is_const_expr(&self) -> bool {
+        match &self.kind {
+            NodeKind::INTEGER { .. }
+            | NodeKind::FLOAT { .. }
+            | NodeKind::STRING(_)
+            | NodeKind::BYTE_STRING(_)
+            | NodeKind::CHAR(_)
+            | NodeKind::BOOL(_)
+            | NodeKind::IDENT(_) => true,
+            NodeKind::UNARY { val, .. } => val.is_const_expr(),
+            NodeKind::BINARY { lhs, rhs, .. } => lhs.is_const_expr() && rhs.is_const_expr(),
+            NodeKind::RANGE { start, end, .. } => start.is_const_expr() && end.is_const_expr(),
+            NodeKind::STRUCT_LIT { fields, .. } => fields.iter().all(Node::is_const_expr),
+            NodeKind::FIELD_INIT { value, .. } => value.is_const_expr(),
+            NodeKind::FIELD_ACCESS { base, .. } => base.is_const_expr(),
+            NodeKind::ARRAY(items) => items.iter().all(Node::is_const_expr),
+            NodeKind::ARRAY_REPEAT { value, count } => value.is_const_expr() && count.is_const_expr(),
+            NodeKind::INDEX { base, index } => base.is_const_expr() && index.is_const_expr(),
+            _ => false
+        }
+    }
+}
+
+impl std::fmt::Debug for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.kind.fmt(f)?;
+        write!(f, " @{}..{}", self.span.start_byte, self.span.end_byte)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub enum Type {
+    VOID,
+    I32,
+    F32,
+    BOOL,
+    NAMED(Symbol),
+    // `&T` can be written through, `&imm T` only read
+    REF{ty: Box<Type>, imm: bool},
+    ARRAY{ty: Box<Type>, len: Box<Node>}
+}
+
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub enum NodeKind {
+    INTEGER{val: u64, suffix: Option<NumSuffix>},
+    FLOAT{val: f64, suffix: Option<NumSuffix>},
+    STRING(String),
+    BYTE_STRING(Vec<u8>),
+    CHAR(char),
+    BOOL(bool),
+    IDENT(Symbol),
+    CALL{callee: Box<Node>, args: Vec<Node>},
+    STRUCT_LIT{name: Symbol, fields: Vec<Node>},
+    FIELD_INIT{name: Symbol, value: Box<Node>},
+    FIELD_ACCESS{base: Box<Node>, field: Symbol},
+    ARRAY(Vec<Node>),
+    ARRAY_REPEAT{value: Box<Node>, count: Box<Node>},
+    INDEX{base: Box<Node>, index: Box<Node>},
     BINARY{lhs: Box<Node>, rhs: Box<Node>, op: TokenKind},
     UNARY{val: Box<Node>, op: TokenKind},
-    VAL{name: String, expr: Box<Node>},
+    REF{val: Box<Node>, imm: bool},
+    DEREF(Box<Node>),
+    VAL{name: Symbol, ty: Option<Type>, imm: bool, expr: Option<Box<Node>>},
+    ASSIGN{target: Box<Node>, value: Box<Node>, op: TokenKind},
+    PARAM{name: Symbol, docs: Vec<String>, ty: Type, imm: bool},
     BLOCK(Vec<Node>),
-    FUNCTION{name: String, arguments: Vec<Node>, block: Box<Node>, ret_type: TokenKind}
-}
\ No newline at end of file
+    IF{cond: Box<Node>, then: Box<Node>, else_: Option<Box<Node>>},
+    WHILE{label: Option<Symbol>, cond: Box<Node>, body: Box<Node>},
+    FOR{label: Option<Symbol>, var: Symbol, iter: Box<Node>, body: Box<Node>},
+    LOOP{label: Option<Symbol>, body: Box<Node>},
+    RANGE{start: Box<Node>, end: Box<Node>, inclusive: bool},
+    BREAK(Option<Symbol>),
+    CONTINUE(Option<Symbol>),
+    RETURN(Option<Box<Node>>),
+    PRINT{args: Vec<Node>, newline: bool},
+    FUNCTION{name: Symbol, docs: Vec<String>, arguments: Vec<Node>, block: Box<Node>, ret_type: Type},
+    CONST{name: Symbol, docs: Vec<String>, ty: Option<Type>, expr: Box<Node>},
+    // top-level `let`, unlike VAL it always has an initializer
+    GLOBAL{name: Symbol, docs: Vec<String>, ty: Option<Type>, imm: bool, expr: Box<Node>},
+    STRUCT{name: Symbol, docs: Vec<String>, fields: Vec<Node>},
+    FIELD{name: Symbol, docs: Vec<String>, ty: Type}
+}
@@ -0,0 +1,70 @@
+use ipic_tokenize::error::LexError;
+use ipic_tokenize::grammar::TokenKind;
+use ipic_tokenize::span::Span;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseErrorKind {
+    EXPECTED_TOKEN(TokenKind),
+    EXPECTED_EXPRESSION,
+    EXPECTED_IDENT,
+    EXPECTED_TYPE,
+    EXPECTED_ITEM,
+    EXPECTED_LOOP,
+    INVALID_ASSIGN_TARGET,
+    NON_CONST_INITIALIZER,
+    INT_OVERFLOW,
+    UNSUPPORTED_OPERATOR
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub found: TokenKind,
+    pub pos: (i32, i32), // row | column
+    pub span: Span,
+    pub text: String
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ParseErrorKind::EXPECTED_TOKEN(kind) => write!(f, "expected {:?}", kind)?,
+            ParseErrorKind::EXPECTED_EXPRESSION => write!(f, "expected expression")?,
+            ParseErrorKind::EXPECTED_IDENT => write!(f, "expected identifier")?,
+            ParseErrorKind::EXPECTED_TYPE => write!(f, "expected type")?,
+            ParseErrorKind::EXPECTED_ITEM => write!(f, "expected item")?,
+            ParseErrorKind::EXPECTED_LOOP => write!(f, "expected loop after label")?,
+            ParseErrorKind::INVALID_ASSIGN_TARGET => write!(f, "invalid left-hand side of assignment")?,
+            ParseErrorKind::NON_CONST_INITIALIZER => write!(f, "global initializer is not a constant expression")?,
+            ParseErrorKind::INT_OVERFLOW => write!(f, "integer literal is too large for its type")?,
+            ParseErrorKind::UNSUPPORTED_OPERATOR => write!(f, "unsupported operator")?
+        };
+
+        write!(f, ", found {:?} {:?} at line: {} | col: {}", self.found, self.text, self.pos.0, self.pos.1)
+    }
+}
+
+// everything reported while turning source into an AST
+#[derive(Clone, Debug, PartialEq)]
+pub enum Diagnostic {
+    LEX(LexError),
+    PARSE(ParseError)
+}
+
+impl Diagnostic {
+    pub fn span(&self) -> Span {
+        match self {
+            Diagnostic::LEX(error) => error.span,
+            Diagnostic::PARSE(error) => error.span
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Diagnostic::LEX(error) => error.fmt(f),
+            Diagnostic::PARSE(error) => error.fmt(f)
+        }
+    }
+}
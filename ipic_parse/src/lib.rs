@@ -1,13 +1,21 @@
+#![allow(clippy::upper_case_acronyms, non_camel_case_types)]
 
 use std::collections::HashMap;
-use ast::Node;
-use ipic_tokenize::{grammar::{LiteralKind, Token, TokenKind}, tokenize, TokenIterator};
-use ipic_tokenize::grammar::{NumberBase, ReservedIDents};
+use ast::{Node, NodeKind, Type};
+use ipic_tokenize::{grammar::{LiteralKind, NumSuffix, Token, TokenKind}, tokenize, unescape, unescape_bytes, TokenIterator};
+use ipic_tokenize::grammar::ReservedIDents;
+use ipic_tokenize::span::Span;
+use ipic_symbol::Symbol;
+use crate::error::{Diagnostic, ParseError, ParseErrorKind};
 use crate::priority_lvl::{get_tok_priority, PriorityLevel};
 
 mod ast;
+pub mod error;
 mod priority_lvl;
 
+#[cfg(test)]
+mod tests;
+
 type PrefixFn<'a> = fn(parser: &mut Parser<'a>) -> Option<Node>;
 type InfixFn<'a> = fn(parse: &mut Parser<'a>, prefix_node: Node) -> Option<Node>;
 
@@ -15,8 +23,15 @@ struct Parser<'a> {
     stream: TokenIterator<'a>,
     infix_callback: HashMap<TokenKind, InfixFn<'a>>,
     prefix_callback: HashMap<TokenKind, PrefixFn<'a>>,
-    peek_tok: Token,
-    current_tok: Token
+    peek_tok: Token<'a>,
+    current_tok: Token<'a>,
+    errors: Vec<ParseError>,
+    // set while parsing `if`/`while`/`for` headers, where `{` opens the body
+    no_struct_lit: bool,
+    // set by a unary `-` for the prefix right after it
+    negate_next: bool,
+    // `{` minus `}` consumed so far, lets recovery skip whole brace groups
+    depth: i32
 }
 
 impl<'a> Parser<'a> {
@@ -26,25 +41,31 @@ impl<'a> Parser<'a> {
             infix_callback: HashMap::new(),
             prefix_callback: HashMap::new(),
             peek_tok: Token::uninited(),
-            current_tok: Token::uninited()
+            current_tok: Token::uninited(),
+            errors: Vec::new(),
+            no_struct_lit: false,
+            negate_next: false,
+            depth: 0
         };
 
-        if obj.stream.toks.size_hint().0 > 2 {
-            obj.bump();
-            obj.bump();
-        }
-
+        obj.peek_tok = obj.stream.toks.peek().copied().unwrap_or(obj.stream.eof);
         obj.fill_prefix_callbacks();
         obj.fill_inifx_callbacks();
 
-        return obj;
+        obj
     }
 
-    fn bump(&mut self) -> Option<Token>{
+    fn bump(&mut self) -> Option<Token<'a>>{
         self.current_tok = self.stream.toks.next()?;
-        self.peek_tok = self.stream.toks.peek()?.clone();
+        self.peek_tok = self.stream.toks.peek().copied().unwrap_or(self.stream.eof);
+
+        match self.current_tok.kind {
+            TokenKind::CLBRACE => self.depth += 1,
+            TokenKind::CRBRACE => self.depth -= 1,
+            _ => ()
+        };
 
-        Some(self.current_tok.clone())
+        Some(self.current_tok)
     }
 
     fn fill_prefix_callbacks(&mut self) {
@@ -54,195 +75,856 @@ impl<'a> Parser<'a> {
         self.prefix_callback.insert(
             TokenKind::LBRACE, Self::parse_in_brace);
 
+        self.prefix_callback.insert(
+            TokenKind::IDENT, Self::parse_ident);
+
+        self.prefix_callback.insert(
+            TokenKind::CLBRACE, Self::parse_exprs_block);
+
+        self.prefix_callback.insert(
+            TokenKind::RESERVED(ReservedIDents::IF), Self::parse_if);
+
+        self.prefix_callback.insert(
+            TokenKind::RESERVED(ReservedIDents::WHILE), Self::parse_while);
+
+        self.prefix_callback.insert(
+            TokenKind::RESERVED(ReservedIDents::FOR), Self::parse_for);
+
+        self.prefix_callback.insert(
+            TokenKind::RESERVED(ReservedIDents::LOOP), Self::parse_loop);
+
+        self.prefix_callback.insert(
+            TokenKind::LABEL, Self::parse_labeled_loop);
+
+        self.prefix_callback.insert(
+            TokenKind::RESERVED(ReservedIDents::BREAK), Self::parse_loop_jump);
+
+        self.prefix_callback.insert(
+            TokenKind::RESERVED(ReservedIDents::CONTINUE), Self::parse_loop_jump);
+
+        self.prefix_callback.insert(
+            TokenKind::RETURN, Self::parse_return);
+
+        self.prefix_callback.insert(
+            TokenKind::PRINT, Self::parse_print);
+
+        self.prefix_callback.insert(
+            TokenKind::PRINTLN, Self::parse_print);
+
         let prefix_ops = vec![
             TokenKind::DECREMENT,
             TokenKind::INCREMENT,
             TokenKind::MINUS,
-            TokenKind::PLUS
+            TokenKind::PLUS,
+            TokenKind::NOT
         ];
 
         for prefix in prefix_ops {
             self.prefix_callback.insert(prefix, Self::parse_unary);
         }
+
+        self.prefix_callback.insert(TokenKind::SLBRACE, Self::parse_array);
+        self.prefix_callback.insert(TokenKind::AMPERSAND, Self::parse_ref);
+        self.prefix_callback.insert(TokenKind::AND, Self::parse_ref);
+        self.prefix_callback.insert(TokenKind::STAR, Self::parse_deref);
     }
 
     fn parse_in_brace(&mut self) -> Option<Node> {
-        let result = self.parse_prioritized_expr(PriorityLevel::LOWEST)?;
-        self.validate_tok_type(TokenKind::RBRACE);
-        return Some(result);
+        let start = self.current_tok.span;
+        let result = self.with_struct_lit(true, |parser| parser.parse_prioritized_expr(PriorityLevel::LOWEST))?;
+        self.validate_tok_type(TokenKind::RBRACE)?;
+        Some(Node::new(result.kind, start.to(self.current_tok.span)))
     }
 
     fn fill_inifx_callbacks(&mut self) {
         let infix_ops = vec![
             TokenKind::STAR,
             TokenKind::SLASH,
+            TokenKind::PERCENT,
             TokenKind::MINUS,
-            TokenKind::PLUS
+            TokenKind::PLUS,
+            TokenKind::SHL,
+            TokenKind::SHR,
+            TokenKind::LT,
+            TokenKind::MT,
+            TokenKind::LTE,
+            TokenKind::MTE,
+            TokenKind::DEQUAL,
+            TokenKind::NEQUAL,
+            TokenKind::AMPERSAND,
+            TokenKind::CARET,
+            TokenKind::PIPE,
+            TokenKind::AND,
+            TokenKind::OR
         ];
 
         for infix in infix_ops {
             self.infix_callback.insert(infix, Self::parse_binary);
         }
+
+        let assign_ops = vec![
+            TokenKind::EQUAL,
+            TokenKind::PLUS_EQUAL,
+            TokenKind::MINUS_EQUAL,
+            TokenKind::STAR_EQUAL,
+            TokenKind::SLASH_EQUAL
+        ];
+
+        for assign in assign_ops {
+            self.infix_callback.insert(assign, Self::parse_assign);
+        }
+
+        self.infix_callback.insert(TokenKind::LBRACE, Self::parse_call);
+        self.infix_callback.insert(TokenKind::SLBRACE, Self::parse_index);
+        self.infix_callback.insert(TokenKind::DOT, Self::parse_field_access);
+        self.infix_callback.insert(TokenKind::RANGE, Self::parse_range);
+        self.infix_callback.insert(TokenKind::RANGE_INCLUSIVE, Self::parse_range);
     }
 
-    fn validate_tok_type(&mut self, needed: TokenKind) {
-        let kind = self.bump().unwrap_or(Token::uninited());
+    // reports the peeked token, it is left in the stream for synchronization
+    fn error<T>(&mut self, kind: ParseErrorKind) -> Option<T> {
+        self.error_at(kind, self.peek_tok)
+    }
 
-        match kind.kind {
-            token if token == needed => (),
-            _ => panic!("Unpredicted token! Waited {:?}, but got {:?}", needed, kind)
-        };
+    fn error_at<T>(&mut self, kind: ParseErrorKind, tok: Token<'a>) -> Option<T> {
+        self.errors.push(ParseError { kind, found: tok.kind, pos: tok.pos, span: tok.span, text: tok.text.to_string() });
+        None
+    }
+
+    fn validate_tok_type(&mut self, needed: TokenKind) -> Option<Token<'a>> {
+        if self.peek_tok.kind == needed {
+            self.bump()
+        } else {
+            self.error(ParseErrorKind::EXPECTED_TOKEN(needed))
+        }
+    }
+
+    fn validate_ident(&mut self) -> Option<Token<'a>> {
+        match self.peek_tok.kind {
+            TokenKind::IDENT => self.bump(),
+            _ => self.error(ParseErrorKind::EXPECTED_IDENT)
+        }
+    }
+
+    fn at_item_start(&self) -> bool {
+        matches!(self.peek_tok.kind,
+            TokenKind::RESERVED(ReservedIDents::FUNCTION | ReservedIDents::STRUCT | ReservedIDents::CONST) | TokenKind::EOF)
+    }
+
+    // skips the rest of a broken statement that started at brace `depth`, braces it opened
+    // are skipped as a whole and the `}` closing the enclosing block is left for it
+    fn synchronize(&mut self, depth: i32) {
+        while !self.at_item_start() {
+            let at_depth = self.depth <= depth;
+            match self.peek_tok.kind {
+                TokenKind::CRBRACE if at_depth => break,
+                TokenKind::SEMICOLON if at_depth => {
+                    self.bump();
+                    break;
+                }
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn skip_to_item(&mut self) {
+        while !self.at_item_start() {
+            self.bump();
+        }
     }
 
     fn parse_binary(&mut self, prefix: Node) -> Option<Node> {
         let operator = self.bump()?.kind;
+        let rhs = self.parse_prioritized_expr(get_tok_priority(&operator))?;
+        let span = prefix.span.to(rhs.span);
 
-        Some(Node::BINARY {
+        Some(Node::new(NodeKind::BINARY {
             lhs: Box::new(prefix),
-            rhs: Box::new(self.parse_prioritized_expr(get_tok_priority(&operator))?),
+            rhs: Box::new(rhs),
             op: operator
-        })
+        }, span))
     }
 
-    fn parse_unary(&mut self) -> Option<Node> {
-        let op = self.current_tok.kind.clone();
-        let expression = self.parse_prioritized_expr(PriorityLevel::PREFIX)?;
+    // assignments are right associative, `a = b = c` assigns `c` to `b` first
+    fn parse_assign(&mut self, target: Node) -> Option<Node> {
+        let operator = self.bump()?;
+
+        if !matches!(target.kind, NodeKind::IDENT(_) | NodeKind::FIELD_ACCESS { .. } | NodeKind::INDEX { .. } | NodeKind::DEREF(_)) {
+            return self.error_at(ParseErrorKind::INVALID_ASSIGN_TARGET, operator);
+        }
+
+        let value = self.parse_prioritized_expr(PriorityLevel::LOWEST)?;
+        let span = target.span.to(value.span);
+
+        Some(Node::new(NodeKind::ASSIGN {
+            target: Box::new(target),
+            value: Box::new(value),
+            op: operator.kind
+        }, span))
+    }
+
+    fn parse_range(&mut self, start: Node) -> Option<Node> {
+        let inclusive = self.bump()?.kind == TokenKind::RANGE_INCLUSIVE;
+        let end = self.parse_prioritized_expr(PriorityLevel::RANGE)?;
+        let span = start.span.to(end.span);
 
-        match op {
-            TokenKind::DECREMENT | TokenKind::INCREMENT => {
-                match expression {
-                    _ => unimplemented!("Not valid operator for increment or decrement")
+        Some(Node::new(NodeKind::RANGE {
+            start: Box::new(start),
+            end: Box::new(end),
+            inclusive
+        }, span))
+    }
+
+    fn parse_field_access(&mut self, base: Node) -> Option<Node> {
+        self.bump();
+        let field = self.validate_ident()?.symbol();
+
+        let span = base.span.to(self.current_tok.span);
+        Some(Node::new(NodeKind::FIELD_ACCESS { base: Box::new(base), field }, span))
+    }
+
+    fn parse_ident(&mut self) -> Option<Node> {
+        if self.peek_tok.kind == TokenKind::CLBRACE && !self.no_struct_lit {
+            return self.parse_struct_lit();
+        }
+
+        Some(Node::new(NodeKind::IDENT(self.current_tok.symbol()), self.current_tok.span))
+    }
+
+    // `Point { x: 1, y: 2 }`
+    fn parse_struct_lit(&mut self) -> Option<Node> {
+        let start = self.current_tok.span;
+        let name = self.current_tok.symbol();
+
+        self.bump();
+        let fields = self.with_struct_lit(true, |parser| parser.parse_comma_list(TokenKind::CRBRACE, Self::parse_field_init))?;
+
+        Some(Node::new(NodeKind::STRUCT_LIT { name, fields }, start.to(self.current_tok.span)))
+    }
+
+    fn parse_field_init(&mut self) -> Option<Node> {
+        let start = self.peek_tok.span;
+        let name = self.validate_ident()?.symbol();
+        self.validate_tok_type(TokenKind::COLON)?;
+        let value = self.parse_prioritized_expr(PriorityLevel::LOWEST)?;
+
+        let span = start.to(value.span);
+        Some(Node::new(NodeKind::FIELD_INIT { name, value: Box::new(value) }, span))
+    }
+
+    fn with_struct_lit<T>(&mut self, allowed: bool, parse: impl FnOnce(&mut Self) -> T) -> T {
+        let saved = std::mem::replace(&mut self.no_struct_lit, !allowed);
+        let result = parse(self);
+        self.no_struct_lit = saved;
+        result
+    }
+
+    fn parse_cond(&mut self) -> Option<Node> {
+        self.with_struct_lit(false, |parser| parser.parse_prioritized_expr(PriorityLevel::LOWEST))
+    }
+
+    // `[1, 2, 3]` or `[0; 16]`
+    fn parse_array(&mut self) -> Option<Node> {
+        let start = self.current_tok.span;
+
+        if self.peek_tok.kind == TokenKind::SRBRACE {
+            self.bump();
+            return Some(Node::new(NodeKind::ARRAY(Vec::new()), start.to(self.current_tok.span)));
+        }
+
+        let first = self.with_struct_lit(true, Self::parse_arg)?;
+
+        let kind = match self.peek_tok.kind {
+            TokenKind::SEMICOLON => {
+                self.bump();
+                let count = self.with_struct_lit(true, Self::parse_arg)?;
+                self.validate_tok_type(TokenKind::SRBRACE)?;
+                NodeKind::ARRAY_REPEAT { value: Box::new(first), count: Box::new(count) }
+            }
+            TokenKind::SRBRACE => {
+                self.bump();
+                NodeKind::ARRAY(vec![first])
+            }
+            _ => {
+                self.validate_tok_type(TokenKind::COMMA)?;
+                let rest = self.with_struct_lit(true, |parser| parser.parse_comma_list(TokenKind::SRBRACE, Self::parse_arg))?;
+                NodeKind::ARRAY(std::iter::once(first).chain(rest).collect())
+            }
+        };
+
+        Some(Node::new(kind, start.to(self.current_tok.span)))
+    }
+
+    fn parse_index(&mut self, base: Node) -> Option<Node> {
+        self.bump();
+        let index = self.with_struct_lit(true, Self::parse_arg)?;
+        self.validate_tok_type(TokenKind::SRBRACE)?;
+
+        let span = base.span.to(self.current_tok.span);
+        Some(Node::new(NodeKind::INDEX { base: Box::new(base), index: Box::new(index) }, span))
+    }
+
+    fn parse_call(&mut self, callee: Node) -> Option<Node> {
+        self.bump();
+        let args = self.parse_args()?;
+
+        let span = callee.span.to(self.current_tok.span);
+        Some(Node::new(NodeKind::CALL { callee: Box::new(callee), args }, span))
+    }
+
+    // `(a, b)`, the `(` is already consumed
+    fn parse_args(&mut self) -> Option<Vec<Node>> {
+        self.with_struct_lit(true, |parser| parser.parse_comma_list(TokenKind::RBRACE, Self::parse_arg))
+    }
+
+    fn parse_arg(&mut self) -> Option<Node> {
+        self.parse_prioritized_expr(PriorityLevel::LOWEST)
+    }
+
+    // items separated by commas with an optional trailing one, up to and including `close`
+    fn parse_comma_list(&mut self, close: TokenKind, parse_item: PrefixFn<'a>) -> Option<Vec<Node>> {
+        let mut items = Vec::new();
+
+        while self.peek_tok.kind != close {
+            items.push(parse_item(self)?);
+
+            if self.peek_tok.kind != close {
+                self.validate_tok_type(TokenKind::COMMA)?;
+            }
+        }
+        self.validate_tok_type(close)?;
+
+        Some(items)
+    }
+
+    // `else if` chains nest as an IF in the else branch
+    fn parse_if(&mut self) -> Option<Node> {
+        let start = self.current_tok.span;
+        let cond = self.parse_cond()?;
+        let then = self.parse_block()?;
+
+        let else_ = match self.peek_tok.kind {
+            TokenKind::RESERVED(ReservedIDents::ELSE) => {
+                self.bump();
+                match self.peek_tok.kind {
+                    TokenKind::RESERVED(ReservedIDents::IF) => {
+                        self.bump();
+                        Some(Box::new(self.parse_if()?))
+                    }
+                    _ => Some(Box::new(self.parse_block()?))
                 }
             }
-            _ => ()
+            _ => None
         };
 
-        Some(Node::UNARY { val: Box::new(expression), op: op.clone() })
+        Some(Node::new(NodeKind::IF {
+            cond: Box::new(cond),
+            then: Box::new(then),
+            else_
+        }, start.to(self.current_tok.span)))
+    }
+
+    fn parse_while(&mut self) -> Option<Node> {
+        self.parse_while_loop(None, self.current_tok.span)
     }
 
-    fn parse_to_num_expr(&mut self, base: NumberBase, val: String) -> Node {
-        let number = match base {
-            NumberBase::BINARY => i32::from_str_radix(val.as_str(), 2),
-            NumberBase::OCTAL => i32::from_str_radix(val.as_str(), 8),
-            NumberBase::DECIMAL => i32::from_str_radix(val.as_str(), 10),
-            NumberBase::HEX => i32::from_str_radix(val.as_str(), 16),
+    fn parse_while_loop(&mut self, label: Option<Symbol>, start: Span) -> Option<Node> {
+        let cond = self.parse_cond()?;
+        let body = self.parse_block()?;
+
+        let span = start.to(body.span);
+        Some(Node::new(NodeKind::WHILE { label, cond: Box::new(cond), body: Box::new(body) }, span))
+    }
+
+    fn parse_for(&mut self) -> Option<Node> {
+        self.parse_for_loop(None, self.current_tok.span)
+    }
+
+    // `for i in 0..n { }`
+    fn parse_for_loop(&mut self, label: Option<Symbol>, start: Span) -> Option<Node> {
+        let var = self.validate_ident()?.symbol();
+        self.validate_tok_type(TokenKind::RESERVED(ReservedIDents::IN))?;
+        let iter = self.parse_cond()?;
+        let body = self.parse_block()?;
+
+        let span = start.to(body.span);
+        Some(Node::new(NodeKind::FOR { label, var, iter: Box::new(iter), body: Box::new(body) }, span))
+    }
+
+    fn parse_loop(&mut self) -> Option<Node> {
+        self.parse_infinite_loop(None, self.current_tok.span)
+    }
+
+    // `loop { }`, only left with `break` or `return`
+    fn parse_infinite_loop(&mut self, label: Option<Symbol>, start: Span) -> Option<Node> {
+        let body = self.parse_block()?;
+
+        let span = start.to(body.span);
+        Some(Node::new(NodeKind::LOOP { label, body: Box::new(body) }, span))
+    }
+
+    // `'outer: for ...`, `'outer: while ...` or `'outer: loop ...`
+    fn parse_labeled_loop(&mut self) -> Option<Node> {
+        let start = self.current_tok.span;
+        let label = Some(self.current_tok.symbol());
+        self.validate_tok_type(TokenKind::COLON)?;
+
+        match self.peek_tok.kind {
+            TokenKind::RESERVED(ReservedIDents::FOR) => {
+                self.bump();
+                self.parse_for_loop(label, start)
+            }
+            TokenKind::RESERVED(ReservedIDents::WHILE) => {
+                self.bump();
+                self.parse_while_loop(label, start)
+            }
+            TokenKind::RESERVED(ReservedIDents::LOOP) => {
+                self.bump();
+                self.parse_infinite_loop(label, start)
+            }
+            _ => self.error(ParseErrorKind::EXPECTED_LOOP)
+        }
+    }
+
+    // `break` and `continue` with an optional `'label`
+    fn parse_loop_jump(&mut self) -> Option<Node> {
+        let is_break = self.current_tok.kind == TokenKind::RESERVED(ReservedIDents::BREAK);
+        let mut span = self.current_tok.span;
+
+        let label = match self.peek_tok.kind {
+            TokenKind::LABEL => {
+                self.bump();
+                span = span.to(self.current_tok.span);
+                Some(self.current_tok.symbol())
+            }
+            _ => None
+        };
+
+        let kind = if is_break {
+            NodeKind::BREAK(label)
+        } else {
+            NodeKind::CONTINUE(label)
+        };
+        Some(Node::new(kind, span))
+    }
+
+    // `return;` or `return x;`, a `}` or EOF right after it means the `;` is missing,
+    // which is left for the statement to report
+    fn parse_return(&mut self) -> Option<Node> {
+        let start = self.current_tok.span;
+
+        let value = match self.peek_tok.kind {
+            TokenKind::SEMICOLON | TokenKind::CRBRACE | TokenKind::EOF => None,
+            _ => Some(Box::new(self.parse_prioritized_expr(PriorityLevel::LOWEST)?))
         };
 
-        Node::INTEGER(number.unwrap())
+        let span = value.as_ref().map_or(start, |value| start.to(value.span));
+        Some(Node::new(NodeKind::RETURN(value), span))
+    }
+
+    // `print(a, b)` and `println(a, b)`
+    fn parse_print(&mut self) -> Option<Node> {
+        let start = self.current_tok.span;
+        let newline = self.current_tok.kind == TokenKind::PRINTLN;
+
+        self.validate_tok_type(TokenKind::LBRACE)?;
+        let args = self.parse_args()?;
+
+        Some(Node::new(NodeKind::PRINT { args, newline }, start.to(self.current_tok.span)))
     }
 
-    fn parse_literal(&mut self, literal: LiteralKind) -> Node {
-        match literal {
-            LiteralKind::INT { base, val } => self.parse_to_num_expr(base, val),
-            _ => panic!("error literal type")
+    fn parse_unary(&mut self) -> Option<Node> {
+        let op = self.current_tok.kind;
+        let start = self.current_tok.span;
+
+        if matches!(op, TokenKind::DECREMENT | TokenKind::INCREMENT) {
+            return self.error_at(ParseErrorKind::UNSUPPORTED_OPERATOR, self.current_tok);
         }
+
+        self.negate_next = op == TokenKind::MINUS;
+        let expression = self.parse_prioritized_expr(PriorityLevel::PREFIX)?;
+
+        let span = start.to(expression.span);
+        Some(Node::new(NodeKind::UNARY { val: Box::new(expression), op }, span))
     }
 
-    fn parse_prefix(&mut self) -> Option<Node> {
-        let tok = self.bump()?;
+    // `&x` or `&imm x`, `&&x` is lexed as one token and takes two references
+    fn parse_ref(&mut self) -> Option<Node> {
+        let start = self.current_tok.span;
+        let is_double = self.current_tok.kind == TokenKind::AND;
+        let imm = self.peek_tok.kind == TokenKind::IMMUTABLE;
+        if imm {
+            self.bump();
+        }
 
-        let prefix = match tok.kind.clone() {
-            TokenKind::LITERAL(lit) => self.parse_literal(lit),
-            _ => {
-                let callback = self.prefix_callback.get(&tok.kind);
-                match callback {
-                    Some(func) => func(self)?,
-                    _ => panic!("unpredicted token kind for lhs parsing")
+        let expression = self.parse_prioritized_expr(PriorityLevel::PREFIX)?;
+        let mut node = Node::new(NodeKind::REF { val: Box::new(expression), imm }, start.to(self.current_tok.span));
+
+        if is_double {
+            let inner = Span::new(start.file_id, start.start_byte + 1, node.span.end_byte);
+            node = Node::new(NodeKind::REF { val: Box::new(Node::new(node.kind, inner)), imm: false }, node.span);
+        }
+        Some(node)
+    }
+
+    fn parse_deref(&mut self) -> Option<Node> {
+        let start = self.current_tok.span;
+        let expression = self.parse_prioritized_expr(PriorityLevel::PREFIX)?;
+
+        let span = start.to(expression.span);
+        Some(Node::new(NodeKind::DEREF(Box::new(expression)), span))
+    }
+
+    fn parse_literal(&mut self, literal: LiteralKind, negated: bool) -> Node {
+        let text = self.current_tok.text;
+
+        // malformed and overflowing literals are already reported by the lexer
+        let kind = match literal {
+            LiteralKind::INT { suffix, .. } => {
+                let val = literal.int_value(text).unwrap_or_default();
+
+                // the lexer lets signed literals go one past the max, only `-` makes that fit
+                let ty = suffix.unwrap_or(NumSuffix::I32);
+                if !negated && ty.is_signed() && val == ty.max_value() + 1 {
+                    self.error_at::<()>(ParseErrorKind::INT_OVERFLOW, self.current_tok);
                 }
+                NodeKind::INTEGER { val, suffix }
             }
+            LiteralKind::FLOAT { suffix } => NodeKind::FLOAT { val: literal.float_value(text).unwrap_or_default(), suffix },
+            LiteralKind::STRING => NodeKind::STRING(unescape(text)),
+            LiteralKind::RAW_STRING { .. } => NodeKind::STRING(literal.raw_value(text).unwrap_or_default().to_string()),
+            LiteralKind::BYTE_STRING => NodeKind::BYTE_STRING(unescape_bytes(text)),
+            LiteralKind::CHAR(val) => NodeKind::CHAR(val),
+            LiteralKind::BOOL(val) => NodeKind::BOOL(val)
+        };
+
+        Node::new(kind, self.current_tok.span)
+    }
+
+    fn parse_prefix(&mut self) -> Option<Node> {
+        let kind = self.peek_tok.kind;
+        let negated = std::mem::take(&mut self.negate_next);
+
+        if let TokenKind::LITERAL(lit) = kind {
+            self.bump();
+            return Some(self.parse_literal(lit, negated));
+        }
+
+        let Some(&callback) = self.prefix_callback.get(&kind) else {
+            return self.error(ParseErrorKind::EXPECTED_EXPRESSION);
         };
 
-        Some(prefix)
+        self.bump();
+        callback(self)
     }
 
     fn parse_prioritized_expr(&mut self, priority: PriorityLevel) -> Option<Node> {
         let mut left = self.parse_prefix()?;
 
-        while let Some(tok) = self.stream.toks.peek() {
-            if priority >= get_tok_priority(&tok.kind) {
+        loop {
+            let kind = self.peek_tok.kind;
+            if priority >= get_tok_priority(&kind) || kind == TokenKind::SEMICOLON {
                 break
             }
 
-            if tok.kind == TokenKind::SEMICOLON {
+            // the caller reports what it expected after the expression
+            let Some(&callback) = self.infix_callback.get(&kind) else {
                 break
-            }
-
-            let callback = self.infix_callback.get(&tok.kind)?;
+            };
             left = callback(self, left)?
         }
         Some(left)
     }
 
     fn parse_statement(&mut self) -> Option<Node> {
+        // block-like statements end at their `}` and need no `;`
+        if matches!(self.peek_tok.kind, TokenKind::CLBRACE | TokenKind::LABEL
+            | TokenKind::RESERVED(ReservedIDents::IF | ReservedIDents::WHILE
+            | ReservedIDents::FOR | ReservedIDents::LOOP)) {
+            let result = self.parse_prefix()?;
+            if self.peek_tok.kind == TokenKind::SEMICOLON {
+                self.bump();
+            }
+            return Some(result);
+        }
+
         let result = self.parse_prioritized_expr(PriorityLevel::LOWEST)?;
-        self.validate_tok_type(TokenKind::SEMICOLON);
+        self.validate_tok_type(TokenKind::SEMICOLON)?;
 
         Some(result)
     }
 
+    fn parse_block(&mut self) -> Option<Node> {
+        self.validate_tok_type(TokenKind::CLBRACE)?;
+        self.parse_exprs_block()
+    }
+
+    // statements up to the closing `}`, the `{` is already consumed
     fn parse_exprs_block(&mut self) -> Option<Node> {
+        let start = self.current_tok.span;
         let mut expressions = Vec::<Node>::new();
 
-        while self.peek_tok.kind != TokenKind::CRBRACE {
-            expressions.push(self.parse_statement()?)
+        loop {
+            // doc comments inside a body have no item to document
+            self.parse_docs();
+            if self.peek_tok.kind == TokenKind::CRBRACE || self.at_item_start() {
+                break;
+            }
+
+            let depth = self.depth;
+            match self.parse_statement() {
+                Some(statement) => expressions.push(statement),
+                None => self.synchronize(depth)
+            }
         }
+        self.validate_tok_type(TokenKind::CRBRACE)?;
 
-        Some(Node::BLOCK(expressions))
+        Some(Node::new(NodeKind::BLOCK(expressions), start.to(self.current_tok.span)))
     }
 
-    fn parse_function(&mut self) -> Option<Node> {
-        let name_tok = self.bump()?;
-
-        let name = match name_tok.kind {
-            TokenKind::IDENT(val) => val,
-            _ => panic!("unpredicted name token kind.")
-        };
+    fn parse_function(&mut self, docs: Vec<String>) -> Option<Node> {
+        let start = self.current_tok.span;
+        let name = self.validate_ident()?.symbol();
 
-        self.validate_tok_type(TokenKind::LBRACE);
-        //arguments
-        self.validate_tok_type(TokenKind::RBRACE);
+        self.validate_tok_type(TokenKind::LBRACE)?;
+        let arguments = self.parse_params()?;
 
-        self.validate_tok_type(TokenKind::CLBRACE);
+        let ret_type = match self.peek_tok.kind {
+            TokenKind::ARROW => {
+                self.bump();
+                self.parse_type()?
+            }
+            _ => Type::VOID
+        };
 
-        let fn_body = self.parse_exprs_block()?;
+        let fn_body = self.parse_block()?;
 
-        Some(Node::FUNCTION {
+        let span = start.to(fn_body.span);
+        Some(Node::new(NodeKind::FUNCTION {
             name,
-            arguments: Vec::new(),
+            docs,
+            arguments,
             block: Box::new(fn_body),
-            ret_type: TokenKind::RESERVED(ReservedIDents::VOID)
-        })
+            ret_type
+        }, span))
+    }
+
+    // `(imm a: i32, b: f32)` with an optional trailing comma, the `(` is already consumed
+    fn parse_params(&mut self) -> Option<Vec<Node>> {
+        self.parse_comma_list(TokenKind::RBRACE, Self::parse_param)
+    }
+
+    fn parse_param(&mut self) -> Option<Node> {
+        let docs = self.parse_docs();
+        let start = self.peek_tok.span;
+        let imm = self.peek_tok.kind == TokenKind::IMMUTABLE;
+        if imm {
+            self.bump();
+        }
+
+        let name = self.validate_ident()?.symbol();
+        self.validate_tok_type(TokenKind::COLON)?;
+        let ty = self.parse_type()?;
+
+        Some(Node::new(NodeKind::PARAM { name, docs, ty, imm }, start.to(self.current_tok.span)))
+    }
+
+    fn parse_type(&mut self) -> Option<Type> {
+        let ty = match self.peek_tok.kind {
+            TokenKind::RESERVED(ReservedIDents::I32) => Type::I32,
+            TokenKind::RESERVED(ReservedIDents::F32) => Type::F32,
+            TokenKind::RESERVED(ReservedIDents::BOOL) => Type::BOOL,
+            TokenKind::RESERVED(ReservedIDents::VOID) => Type::VOID,
+            TokenKind::IDENT => Type::NAMED(self.peek_tok.symbol()),
+            TokenKind::AMPERSAND | TokenKind::AND => return self.parse_ref_type(),
+            TokenKind::SLBRACE => return self.parse_array_type(),
+            _ => return self.error(ParseErrorKind::EXPECTED_TYPE)
+        };
+
+        self.bump();
+        Some(ty)
+    }
+
+    // `[i32; 4]`, whether the length is constant is checked once names are resolved
+    fn parse_array_type(&mut self) -> Option<Type> {
+        self.bump();
+        let ty = self.parse_type()?;
+        self.validate_tok_type(TokenKind::SEMICOLON)?;
+
+        let len = self.parse_prioritized_expr(PriorityLevel::LOWEST)?;
+        self.validate_tok_type(TokenKind::SRBRACE)?;
+
+        Some(Type::ARRAY { ty: Box::new(ty), len: Box::new(len) })
+    }
+
+    // `&T` or `&imm T`
+    fn parse_ref_type(&mut self) -> Option<Type> {
+        let is_double = self.bump()?.kind == TokenKind::AND;
+        let imm = self.peek_tok.kind == TokenKind::IMMUTABLE;
+        if imm {
+            self.bump();
+        }
+
+        let ty = Type::REF { ty: Box::new(self.parse_type()?), imm };
+        if is_double {
+            Some(Type::REF { ty: Box::new(ty), imm: false })
+        } else {
+            Some(ty)
+        }
+    }
+
+    // `struct Point { x: i32, y: i32 }`
+    fn parse_struct(&mut self, docs: Vec<String>) -> Option<Node> {
+        let start = self.current_tok.span;
+        let name = self.validate_ident()?.symbol();
+
+        self.validate_tok_type(TokenKind::CLBRACE)?;
+        let fields = self.parse_comma_list(TokenKind::CRBRACE, Self::parse_field)?;
+
+        Some(Node::new(NodeKind::STRUCT { name, docs, fields }, start.to(self.current_tok.span)))
+    }
+
+    fn parse_field(&mut self) -> Option<Node> {
+        let docs = self.parse_docs();
+        let start = self.peek_tok.span;
+        let name = self.validate_ident()?.symbol();
+        self.validate_tok_type(TokenKind::COLON)?;
+        let ty = self.parse_type()?;
+
+        Some(Node::new(NodeKind::FIELD { name, docs, ty }, start.to(self.current_tok.span)))
     }
 
+    // `let imm x: i32 = 1`, either the type or the initializer can be left out
     fn parse_var(&mut self) -> Option<Node> {
-        let name = self.bump()?;
-        match name.kind {
-            TokenKind::IDENT(val) => {
-                self.validate_tok_type(TokenKind::EQUAL);
-                Some(Node::VAL {
-                    name: val,
-                    expr: Box::new(self.parse_prioritized_expr(PriorityLevel::LOWEST)?)
-                })
+        let start = self.current_tok.span;
+        let (imm, name, ty) = self.parse_binding()?;
+
+        let expr = match (&ty, self.peek_tok.kind) {
+            (Some(_), TokenKind::SEMICOLON) => None,
+            _ => {
+                self.validate_tok_type(TokenKind::EQUAL)?;
+                Some(Box::new(self.parse_prioritized_expr(PriorityLevel::LOWEST)?))
             }
-            _ => panic!("not valid type for value name")
+        };
+
+        Some(Node::new(NodeKind::VAL { name, ty, imm, expr }, start.to(self.current_tok.span)))
+    }
+
+    // `imm x: i32` part of a `let`, the type is optional
+    fn parse_binding(&mut self) -> Option<(bool, Symbol, Option<Type>)> {
+        let imm = self.peek_tok.kind == TokenKind::IMMUTABLE;
+        if imm {
+            self.bump();
         }
+
+        let name = self.validate_ident()?.symbol();
+
+        let ty = match self.peek_tok.kind {
+            TokenKind::COLON => {
+                self.bump();
+                Some(self.parse_type()?)
+            }
+            _ => None
+        };
+
+        Some((imm, name, ty))
+    }
+
+    // top-level `let` and `const`, their initializers have to be known at compile time
+    fn parse_global(&mut self, docs: Vec<String>) -> Option<Node> {
+        let start = self.current_tok.span;
+        let is_const = self.current_tok.kind == TokenKind::RESERVED(ReservedIDents::CONST);
+
+        let (imm, name, ty) = self.parse_binding()?;
+
+        self.validate_tok_type(TokenKind::EQUAL)?;
+        let init_tok = self.peek_tok;
+        let expr = Box::new(self.parse_prioritized_expr(PriorityLevel::LOWEST)?);
+
+        if !expr.is_const_expr() {
+            return self.error_at(ParseErrorKind::NON_CONST_INITIALIZER, init_tok);
+        }
+
+        let span = start.to(self.current_tok.span);
+        self.validate_tok_type(TokenKind::SEMICOLON)?;
+
+        let kind = if is_const {
+            NodeKind::CONST { name, docs, ty, expr }
+        } else {
+            NodeKind::GLOBAL { name, docs, ty, imm, expr }
+        };
+        Some(Node::new(kind, span))
+    }
+
+    fn parse_docs(&mut self) -> Vec<String> {
+        let mut docs = Vec::new();
+
+        loop {
+            match self.peek_tok.kind {
+                // strip the `///` marker
+                TokenKind::DOC_COMMENT => docs.push(self.peek_tok.text[3..].to_string()),
+                TokenKind::INNER_DOC_COMMENT => (),
+                _ => break
+            };
+            self.bump();
+        }
+
+        docs
     }
 
     pub fn parse_node(&mut self) -> Option<Node> {
-        let tok = self.bump()?;
+        let docs = self.parse_docs();
 
-        match tok.kind {
-            //TokenKind::LET => self.parse_var(), // global variable
-            TokenKind::RESERVED(ReservedIDents::FUNCTION) => self.parse_function(),
-            _ => panic!("error type of token to parse Node: {:?}", tok)
+        let node = match self.peek_tok.kind {
+            TokenKind::RESERVED(ReservedIDents::LET | ReservedIDents::CONST) => {
+                let depth = self.depth;
+                self.bump();
+                let node = self.parse_global(docs);
+
+                // a broken global ends at its `;`
+                if node.is_none() {
+                    self.synchronize(depth);
+                }
+                return node;
+            }
+            TokenKind::RESERVED(ReservedIDents::FUNCTION) => {
+                self.bump();
+                self.parse_function(docs)
+            }
+            TokenKind::RESERVED(ReservedIDents::STRUCT) => {
+                self.bump();
+                self.parse_struct(docs)
+            }
+            TokenKind::EOF => None,
+            _ => {
+                let node = self.error(ParseErrorKind::EXPECTED_ITEM);
+                self.bump();
+                node
+            }
+        };
+
+        if node.is_none() {
+            self.skip_to_item();
         }
+        node
     }
 }
 
-pub fn create_parse_stream(input: &str) -> impl Iterator<Item = Node> + '_ {
-    let tok_stream = tokenize(input);
+// lexer and parser diagnostics are returned together, ordered by their position
+pub fn create_parse_stream(input: &str) -> (Vec<Node>, Vec<Diagnostic>) {
+    let (tok_stream, lex_errors) = tokenize(input);
     let mut parser = Parser::new(tok_stream);
-    std::iter::from_fn(move || {
-        parser.parse_node()
-    })
+    let mut nodes = Vec::new();
+
+    while parser.peek_tok.kind != TokenKind::EOF {
+        nodes.extend(parser.parse_node());
+    }
+
+    let mut diagnostics: Vec<Diagnostic> = lex_errors.into_iter().map(Diagnostic::LEX)
+    .chain(parser.errors.into_iter().map(Diagnostic::PARSE)).collect();
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span().start_byte);
+
+    (nodes, diagnostics)
 }
\ No newline at end of file
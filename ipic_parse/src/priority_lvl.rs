@@ -3,8 +3,17 @@ use ipic_tokenize::grammar::TokenKind;
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PriorityLevel {
     LOWEST = 0,
+    ASSIGN,
+    RANGE,
+    OR,
+    AND,
+    // comparisons bind looser than bitwise operators, so `x & MASK == 0` compares the masked value
     EQUAL,
     CMP,
+    BIT_OR,
+    BIT_XOR,
+    BIT_AND,
+    SHIFT,
     SUM_SUB,
     DIV_MUL,
     PREFIX,
@@ -13,11 +22,23 @@ pub enum PriorityLevel {
 
 pub fn get_tok_priority(token: &TokenKind) -> PriorityLevel {
     match token {
-        TokenKind::LBRACE => PriorityLevel::LBRACE,
-        TokenKind::SLASH | TokenKind::STAR => PriorityLevel::DIV_MUL,
+        TokenKind::LBRACE | TokenKind::SLBRACE | TokenKind::DOT => PriorityLevel::LBRACE,
+        TokenKind::SLASH | TokenKind::STAR | TokenKind::PERCENT => PriorityLevel::DIV_MUL,
         TokenKind::PLUS | TokenKind::MINUS => PriorityLevel::SUM_SUB,
-        TokenKind::LT | TokenKind::MT => PriorityLevel::CMP,
-        TokenKind::DEQUAL => PriorityLevel::EQUAL,
+        TokenKind::SHL | TokenKind::SHR => PriorityLevel::SHIFT,
+        TokenKind::LT | TokenKind::MT | TokenKind::LTE | TokenKind::MTE => PriorityLevel::CMP,
+        TokenKind::DEQUAL | TokenKind::NEQUAL => PriorityLevel::EQUAL,
+        TokenKind::AMPERSAND => PriorityLevel::BIT_AND,
+        TokenKind::CARET => PriorityLevel::BIT_XOR,
+        TokenKind::PIPE => PriorityLevel::BIT_OR,
+        TokenKind::AND => PriorityLevel::AND,
+        TokenKind::OR => PriorityLevel::OR,
+        TokenKind::RANGE | TokenKind::RANGE_INCLUSIVE => PriorityLevel::RANGE,
+        TokenKind::EQUAL
+        | TokenKind::PLUS_EQUAL
+        | TokenKind::MINUS_EQUAL
+        | TokenKind::STAR_EQUAL
+        | TokenKind::SLASH_EQUAL => PriorityLevel::ASSIGN,
         _ => PriorityLevel::LOWEST,
     }
-}
\ No newline at end of file
+}
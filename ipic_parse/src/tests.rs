@@ -2,9 +2,1515 @@ use super::*;
 use expect_test::{expect, Expect};
 
 fn check_parsing(src: &str, expect: Expect) {
+    let (nodes, diagnostics) = create_parse_stream(src);
+    let mut parsed: String = nodes.iter()
+    .map(|node| {format!("{:#?}\n", node)}).collect();
+
+    for diagnostic in diagnostics {
+        parsed += &format!("{}\n", diagnostic);
+    }
+
+    expect.assert_eq(&parsed);
 }
 
 #[test]
 fn test_parser() {
+    check_parsing("fn main() { 1 + 2 * 3 % 4; }", expect![[r#"
+        FUNCTION {
+            name: "main",
+            docs: [],
+            arguments: [],
+            block: BLOCK(
+                [
+                    BINARY {
+                        lhs: INTEGER {
+                            val: 1,
+                            suffix: None,
+                        } @12..13,
+                        rhs: BINARY {
+                            lhs: BINARY {
+                                lhs: INTEGER {
+                                    val: 2,
+                                    suffix: None,
+                                } @16..17,
+                                rhs: INTEGER {
+                                    val: 3,
+                                    suffix: None,
+                                } @20..21,
+                                op: STAR,
+                            } @16..21,
+                            rhs: INTEGER {
+                                val: 4,
+                                suffix: None,
+                            } @24..25,
+                            op: PERCENT,
+                        } @16..25,
+                        op: PLUS,
+                    } @12..25,
+                ],
+            ) @10..28,
+            ret_type: VOID,
+        } @0..28
+    "#]]);
+}
+
+#[test]
+fn test_operator_priority() {
+    check_parsing(
+        "fn main() { !1 || 2 && 3 | 4 ^ 5 & 6 << 7 - 8 == 9 < 10; x & MASK == 0; }",
+        expect![[r#"
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        BINARY {
+                            lhs: UNARY {
+                                val: INTEGER {
+                                    val: 1,
+                                    suffix: None,
+                                } @13..14,
+                                op: NOT,
+                            } @12..14,
+                            rhs: BINARY {
+                                lhs: INTEGER {
+                                    val: 2,
+                                    suffix: None,
+                                } @18..19,
+                                rhs: BINARY {
+                                    lhs: BINARY {
+                                        lhs: INTEGER {
+                                            val: 3,
+                                            suffix: None,
+                                        } @23..24,
+                                        rhs: BINARY {
+                                            lhs: INTEGER {
+                                                val: 4,
+                                                suffix: None,
+                                            } @27..28,
+                                            rhs: BINARY {
+                                                lhs: INTEGER {
+                                                    val: 5,
+                                                    suffix: None,
+                                                } @31..32,
+                                                rhs: BINARY {
+                                                    lhs: INTEGER {
+                                                        val: 6,
+                                                        suffix: None,
+                                                    } @35..36,
+                                                    rhs: BINARY {
+                                                        lhs: INTEGER {
+                                                            val: 7,
+                                                            suffix: None,
+                                                        } @40..41,
+                                                        rhs: INTEGER {
+                                                            val: 8,
+                                                            suffix: None,
+                                                        } @44..45,
+                                                        op: MINUS,
+                                                    } @40..45,
+                                                    op: SHL,
+                                                } @35..45,
+                                                op: AMPERSAND,
+                                            } @31..45,
+                                            op: CARET,
+                                        } @27..45,
+                                        op: PIPE,
+                                    } @23..45,
+                                    rhs: BINARY {
+                                        lhs: INTEGER {
+                                            val: 9,
+                                            suffix: None,
+                                        } @49..50,
+                                        rhs: INTEGER {
+                                            val: 10,
+                                            suffix: None,
+                                        } @53..55,
+                                        op: LT,
+                                    } @49..55,
+                                    op: DEQUAL,
+                                } @23..55,
+                                op: AND,
+                            } @18..55,
+                            op: OR,
+                        } @12..55,
+                        BINARY {
+                            lhs: BINARY {
+                                lhs: IDENT(
+                                    "x",
+                                ) @57..58,
+                                rhs: IDENT(
+                                    "MASK",
+                                ) @61..65,
+                                op: AMPERSAND,
+                            } @57..65,
+                            rhs: INTEGER {
+                                val: 0,
+                                suffix: None,
+                            } @69..70,
+                            op: DEQUAL,
+                        } @57..70,
+                    ],
+                ) @10..73,
+                ret_type: VOID,
+            } @0..73
+        "#]]
+    );
+}
+
+#[test]
+fn test_negative_literals() {
+    check_parsing(
+        "fn main() { -2147483648; 2147483648; -(2147483648); -128i8; 128i8; -2147483649; }",
+        expect![[r#"
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        UNARY {
+                            val: INTEGER {
+                                val: 2147483648,
+                                suffix: None,
+                            } @13..23,
+                            op: MINUS,
+                        } @12..23,
+                        INTEGER {
+                            val: 2147483648,
+                            suffix: None,
+                        } @25..35,
+                        UNARY {
+                            val: INTEGER {
+                                val: 2147483648,
+                                suffix: None,
+                            } @38..50,
+                            op: MINUS,
+                        } @37..50,
+                        UNARY {
+                            val: INTEGER {
+                                val: 128,
+                                suffix: Some(
+                                    I8,
+                                ),
+                            } @53..58,
+                            op: MINUS,
+                        } @52..58,
+                        INTEGER {
+                            val: 128,
+                            suffix: Some(
+                                I8,
+                            ),
+                        } @60..65,
+                        UNARY {
+                            val: INTEGER {
+                                val: 2147483649,
+                                suffix: None,
+                            } @68..78,
+                            op: MINUS,
+                        } @67..78,
+                    ],
+                ) @10..81,
+                ret_type: VOID,
+            } @0..81
+            integer literal is too large for its type, found LITERAL(INT { base: DECIMAL, suffix: None }) "2147483648" at line: 1 | col: 26
+            integer literal is too large for its type, found LITERAL(INT { base: DECIMAL, suffix: None }) "2147483648" at line: 1 | col: 40
+            integer literal is too large for its type, found LITERAL(INT { base: DECIMAL, suffix: Some(I8) }) "128i8" at line: 1 | col: 61
+            integer literal is too large for its type "2147483649" at line: 1 | col: 69
+        "#]]
+    );
+}
+
+#[test]
+fn test_char_and_bool_literals() {
+    check_parsing(
+        "fn main() { let c = '\\n'; let b = true; let u = '\\u{1F600}'; f(false); }",
+        expect![[r#"
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        VAL {
+                            name: "c",
+                            ty: None,
+                            imm: false,
+                            expr: Some(
+                                CHAR(
+                                    '\n',
+                                ) @20..24,
+                            ),
+                        } @12..24,
+                        VAL {
+                            name: "b",
+                            ty: None,
+                            imm: false,
+                            expr: Some(
+                                BOOL(
+                                    true,
+                                ) @34..38,
+                            ),
+                        } @26..38,
+                        VAL {
+                            name: "u",
+                            ty: None,
+                            imm: false,
+                            expr: Some(
+                                CHAR(
+                                    '😀',
+                                ) @48..59,
+                            ),
+                        } @40..59,
+                        CALL {
+                            callee: IDENT(
+                                "f",
+                            ) @61..62,
+                            args: [
+                                BOOL(
+                                    false,
+                                ) @63..68,
+                            ],
+                        } @61..69,
+                    ],
+                ) @10..72,
+                ret_type: VOID,
+            } @0..72
+        "#]]
+    );
+}
+
+#[test]
+fn test_raw_and_byte_strings() {
+    check_parsing(
+        "fn main() { let r = r#\"a\\b\"#; let b = b\"x\\n\"; let s = \"x\\n\"; }",
+        expect![[r#"
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        VAL {
+                            name: "r",
+                            ty: None,
+                            imm: false,
+                            expr: Some(
+                                STRING(
+                                    "a\\b",
+                                ) @20..28,
+                            ),
+                        } @12..28,
+                        VAL {
+                            name: "b",
+                            ty: None,
+                            imm: false,
+                            expr: Some(
+                                BYTE_STRING(
+                                    [
+                                        120,
+                                        10,
+                                    ],
+                                ) @38..44,
+                            ),
+                        } @30..44,
+                        VAL {
+                            name: "s",
+                            ty: None,
+                            imm: false,
+                            expr: Some(
+                                STRING(
+                                    "x\n",
+                                ) @54..59,
+                            ),
+                        } @46..59,
+                    ],
+                ) @10..62,
+                ret_type: VOID,
+            } @0..62
+        "#]]
+    );
+}
+
+#[test]
+fn test_error_recovery() {
+    check_parsing(
+        "fn main() { 1 + ; let = 2; (3 4; 5 } 6 fn other() { 7 $ }",
+        expect![[r#"
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [],
+                ) @10..36,
+                ret_type: VOID,
+            } @0..36
+            FUNCTION {
+                name: "other",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [],
+                ) @50..57,
+                ret_type: VOID,
+            } @39..57
+            expected expression, found SEMICOLON ";" at line: 1 | col: 17
+            expected identifier, found EQUAL "=" at line: 1 | col: 23
+            expected RBRACE, found LITERAL(INT { base: DECIMAL, suffix: None }) "4" at line: 1 | col: 31
+            expected SEMICOLON, found CRBRACE "}" at line: 1 | col: 36
+            expected item, found LITERAL(INT { base: DECIMAL, suffix: None }) "6" at line: 1 | col: 38
+            undefined token "$" at line: 1 | col: 55
+            expected SEMICOLON, found CRBRACE "}" at line: 1 | col: 57
+        "#]]
+    );
+}
+
+#[test]
+fn test_recovery_skips_brace_groups() {
+    check_parsing(
+        "fn main() { let p = Point { x: 1 y: 2 }; q; let = { 1 }; r; } fn g() {}",
+        expect![[r#"
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        IDENT(
+                            "q",
+                        ) @41..42,
+                        IDENT(
+                            "r",
+                        ) @57..58,
+                    ],
+                ) @10..61,
+                ret_type: VOID,
+            } @0..61
+            FUNCTION {
+                name: "g",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [],
+                ) @69..71,
+                ret_type: VOID,
+            } @62..71
+            expected COMMA, found IDENT "y" at line: 1 | col: 34
+            expected identifier, found EQUAL "=" at line: 1 | col: 49
+        "#]]
+    );
+}
+
+#[test]
+fn test_function_params() {
+    check_parsing(
+        "fn add(a: i32, imm b: i32,) -> i32 { 1; } fn flag(on: bool) { } fn bad(x i32, y: str) -> { }",
+        expect![[r#"
+            FUNCTION {
+                name: "add",
+                docs: [],
+                arguments: [
+                    PARAM {
+                        name: "a",
+                        docs: [],
+                        ty: I32,
+                        imm: false,
+                    } @7..13,
+                    PARAM {
+                        name: "b",
+                        docs: [],
+                        ty: I32,
+                        imm: true,
+                    } @15..25,
+                ],
+                block: BLOCK(
+                    [
+                        INTEGER {
+                            val: 1,
+                            suffix: None,
+                        } @37..38,
+                    ],
+                ) @35..41,
+                ret_type: I32,
+            } @0..41
+            FUNCTION {
+                name: "flag",
+                docs: [],
+                arguments: [
+                    PARAM {
+                        name: "on",
+                        docs: [],
+                        ty: BOOL,
+                        imm: false,
+                    } @50..58,
+                ],
+                block: BLOCK(
+                    [],
+                ) @60..63,
+                ret_type: VOID,
+            } @42..63
+            expected COLON, found RESERVED(I32) "i32" at line: 1 | col: 74
+        "#]]
+    );
+}
+
+#[test]
+fn test_documented_params() {
+    check_parsing(
+        "fn f(\n    /// the a\n    a: i32,\n    /// the b\n    imm b: f32\n) {}",
+        expect![[r#"
+            FUNCTION {
+                name: "f",
+                docs: [],
+                arguments: [
+                    PARAM {
+                        name: "a",
+                        docs: [
+                            " the a",
+                        ],
+                        ty: I32,
+                        imm: false,
+                    } @24..30,
+                    PARAM {
+                        name: "b",
+                        docs: [
+                            " the b",
+                        ],
+                        ty: F32,
+                        imm: true,
+                    } @50..60,
+                ],
+                block: BLOCK(
+                    [],
+                ) @63..65,
+                ret_type: VOID,
+            } @0..65
+        "#]]
+    );
+}
+
+#[test]
+fn test_idents_and_calls() {
+    check_parsing(
+        "fn main() { let b = a + 1; add(b, -2,) * f()(x); }",
+        expect![[r#"
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        VAL {
+                            name: "b",
+                            ty: None,
+                            imm: false,
+                            expr: Some(
+                                BINARY {
+                                    lhs: IDENT(
+                                        "a",
+                                    ) @20..21,
+                                    rhs: INTEGER {
+                                        val: 1,
+                                        suffix: None,
+                                    } @24..25,
+                                    op: PLUS,
+                                } @20..25,
+                            ),
+                        } @12..25,
+                        BINARY {
+                            lhs: CALL {
+                                callee: IDENT(
+                                    "add",
+                                ) @27..30,
+                                args: [
+                                    IDENT(
+                                        "b",
+                                    ) @31..32,
+                                    UNARY {
+                                        val: INTEGER {
+                                            val: 2,
+                                            suffix: None,
+                                        } @35..36,
+                                        op: MINUS,
+                                    } @34..36,
+                                ],
+                            } @27..38,
+                            rhs: CALL {
+                                callee: CALL {
+                                    callee: IDENT(
+                                        "f",
+                                    ) @41..42,
+                                    args: [],
+                                } @41..44,
+                                args: [
+                                    IDENT(
+                                        "x",
+                                    ) @45..46,
+                                ],
+                            } @41..47,
+                            op: STAR,
+                        } @27..47,
+                    ],
+                ) @10..50,
+                ret_type: VOID,
+            } @0..50
+        "#]]
+    );
+}
+
+#[test]
+fn test_return_and_print() {
+    check_parsing(
+        r#"fn main() { println("hello", 1); print(); return; } fn one() -> i32 { return 1 + 0; } fn bad() { print 1; }"#,
+        expect![[r#"
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        PRINT {
+                            args: [
+                                STRING(
+                                    "hello",
+                                ) @20..27,
+                                INTEGER {
+                                    val: 1,
+                                    suffix: None,
+                                } @29..30,
+                            ],
+                            newline: true,
+                        } @12..31,
+                        PRINT {
+                            args: [],
+                            newline: false,
+                        } @33..40,
+                        RETURN(
+                            None,
+                        ) @42..48,
+                    ],
+                ) @10..51,
+                ret_type: VOID,
+            } @0..51
+            FUNCTION {
+                name: "one",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        RETURN(
+                            Some(
+                                BINARY {
+                                    lhs: INTEGER {
+                                        val: 1,
+                                        suffix: None,
+                                    } @77..78,
+                                    rhs: INTEGER {
+                                        val: 0,
+                                        suffix: None,
+                                    } @81..82,
+                                    op: PLUS,
+                                } @77..82,
+                            ),
+                        ) @70..82,
+                    ],
+                ) @68..85,
+                ret_type: I32,
+            } @52..85
+            FUNCTION {
+                name: "bad",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [],
+                ) @95..107,
+                ret_type: VOID,
+            } @86..107
+            expected LBRACE, found LITERAL(INT { base: DECIMAL, suffix: None }) "1" at line: 1 | col: 104
+        "#]]
+    );
+}
+
+#[test]
+fn test_return_needs_semicolon() {
+    check_parsing(
+        "fn f() { if a { return; } } fn g() { if a { return } }",
+        expect![[r#"
+            FUNCTION {
+                name: "f",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        IF {
+                            cond: IDENT(
+                                "a",
+                            ) @12..13,
+                            then: BLOCK(
+                                [
+                                    RETURN(
+                                        None,
+                                    ) @16..22,
+                                ],
+                            ) @14..25,
+                            else_: None,
+                        } @9..25,
+                    ],
+                ) @7..27,
+                ret_type: VOID,
+            } @0..27
+            FUNCTION {
+                name: "g",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        IF {
+                            cond: IDENT(
+                                "a",
+                            ) @40..41,
+                            then: BLOCK(
+                                [],
+                            ) @42..52,
+                            else_: None,
+                        } @37..52,
+                    ],
+                ) @35..54,
+                ret_type: VOID,
+            } @28..54
+            expected SEMICOLON, found CRBRACE "}" at line: 1 | col: 52
+        "#]]
+    );
+}
+
+#[test]
+fn test_bindings_and_assignment() {
+    check_parsing(
+        "fn main() { let x: i32 = 1; let imm y = 2.5; let z: bool; x = y = 3; x += 1 * 2; 1 = x; let w; }",
+        expect![[r#"
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        VAL {
+                            name: "x",
+                            ty: Some(
+                                I32,
+                            ),
+                            imm: false,
+                            expr: Some(
+                                INTEGER {
+                                    val: 1,
+                                    suffix: None,
+                                } @25..26,
+                            ),
+                        } @12..26,
+                        VAL {
+                            name: "y",
+                            ty: None,
+                            imm: true,
+                            expr: Some(
+                                FLOAT {
+                                    val: 2.5,
+                                    suffix: None,
+                                } @40..43,
+                            ),
+                        } @28..43,
+                        VAL {
+                            name: "z",
+                            ty: Some(
+                                BOOL,
+                            ),
+                            imm: false,
+                            expr: None,
+                        } @45..56,
+                        ASSIGN {
+                            target: IDENT(
+                                "x",
+                            ) @58..59,
+                            value: ASSIGN {
+                                target: IDENT(
+                                    "y",
+                                ) @62..63,
+                                value: INTEGER {
+                                    val: 3,
+                                    suffix: None,
+                                } @66..67,
+                                op: EQUAL,
+                            } @62..67,
+                            op: EQUAL,
+                        } @58..67,
+                        ASSIGN {
+                            target: IDENT(
+                                "x",
+                            ) @69..70,
+                            value: BINARY {
+                                lhs: INTEGER {
+                                    val: 1,
+                                    suffix: None,
+                                } @74..75,
+                                rhs: INTEGER {
+                                    val: 2,
+                                    suffix: None,
+                                } @78..79,
+                                op: STAR,
+                            } @74..79,
+                            op: PLUS_EQUAL,
+                        } @69..79,
+                    ],
+                ) @10..96,
+                ret_type: VOID,
+            } @0..96
+            invalid left-hand side of assignment, found EQUAL "=" at line: 1 | col: 84
+            expected EQUAL, found SEMICOLON ";" at line: 1 | col: 94
+        "#]]
+    );
+}
 
-}
\ No newline at end of file
+#[test]
+fn test_if_and_while() {
+    check_parsing(
+        "fn main() { if a < 1 { b; } else if a < 2 { c; } else { d; } while x { { y; } x -= 1; } if z { 1 + ; } w; }",
+        expect![[r#"
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        IF {
+                            cond: BINARY {
+                                lhs: IDENT(
+                                    "a",
+                                ) @15..16,
+                                rhs: INTEGER {
+                                    val: 1,
+                                    suffix: None,
+                                } @19..20,
+                                op: LT,
+                            } @15..20,
+                            then: BLOCK(
+                                [
+                                    IDENT(
+                                        "b",
+                                    ) @23..24,
+                                ],
+                            ) @21..27,
+                            else_: Some(
+                                IF {
+                                    cond: BINARY {
+                                        lhs: IDENT(
+                                            "a",
+                                        ) @36..37,
+                                        rhs: INTEGER {
+                                            val: 2,
+                                            suffix: None,
+                                        } @40..41,
+                                        op: LT,
+                                    } @36..41,
+                                    then: BLOCK(
+                                        [
+                                            IDENT(
+                                                "c",
+                                            ) @44..45,
+                                        ],
+                                    ) @42..48,
+                                    else_: Some(
+                                        BLOCK(
+                                            [
+                                                IDENT(
+                                                    "d",
+                                                ) @56..57,
+                                            ],
+                                        ) @54..60,
+                                    ),
+                                } @33..60,
+                            ),
+                        } @12..60,
+                        WHILE {
+                            label: None,
+                            cond: IDENT(
+                                "x",
+                            ) @67..68,
+                            body: BLOCK(
+                                [
+                                    BLOCK(
+                                        [
+                                            IDENT(
+                                                "y",
+                                            ) @73..74,
+                                        ],
+                                    ) @71..77,
+                                    ASSIGN {
+                                        target: IDENT(
+                                            "x",
+                                        ) @78..79,
+                                        value: INTEGER {
+                                            val: 1,
+                                            suffix: None,
+                                        } @83..84,
+                                        op: MINUS_EQUAL,
+                                    } @78..84,
+                                ],
+                            ) @69..87,
+                        } @61..87,
+                        IF {
+                            cond: IDENT(
+                                "z",
+                            ) @91..92,
+                            then: BLOCK(
+                                [],
+                            ) @93..102,
+                            else_: None,
+                        } @88..102,
+                        IDENT(
+                            "w",
+                        ) @103..104,
+                    ],
+                ) @10..107,
+                ret_type: VOID,
+            } @0..107
+            expected expression, found SEMICOLON ";" at line: 1 | col: 100
+        "#]]
+    );
+}
+
+#[test]
+fn test_for_ranges_and_jumps() {
+    check_parsing(
+        "fn main() { 'outer: for i in 0..n + 1 { for j in i..=10 { continue 'outer; } break; } let r = a..b; 'x: 1; }",
+        expect![[r#"
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        FOR {
+                            label: Some(
+                                "outer",
+                            ),
+                            var: "i",
+                            iter: RANGE {
+                                start: INTEGER {
+                                    val: 0,
+                                    suffix: None,
+                                } @29..30,
+                                end: BINARY {
+                                    lhs: IDENT(
+                                        "n",
+                                    ) @32..33,
+                                    rhs: INTEGER {
+                                        val: 1,
+                                        suffix: None,
+                                    } @36..37,
+                                    op: PLUS,
+                                } @32..37,
+                                inclusive: false,
+                            } @29..37,
+                            body: BLOCK(
+                                [
+                                    FOR {
+                                        label: None,
+                                        var: "j",
+                                        iter: RANGE {
+                                            start: IDENT(
+                                                "i",
+                                            ) @49..50,
+                                            end: INTEGER {
+                                                val: 10,
+                                                suffix: None,
+                                            } @53..55,
+                                            inclusive: true,
+                                        } @49..55,
+                                        body: BLOCK(
+                                            [
+                                                CONTINUE(
+                                                    Some(
+                                                        "outer",
+                                                    ),
+                                                ) @58..73,
+                                            ],
+                                        ) @56..76,
+                                    } @40..76,
+                                    BREAK(
+                                        None,
+                                    ) @77..82,
+                                ],
+                            ) @38..85,
+                        } @12..85,
+                        VAL {
+                            name: "r",
+                            ty: None,
+                            imm: false,
+                            expr: Some(
+                                RANGE {
+                                    start: IDENT(
+                                        "a",
+                                    ) @94..95,
+                                    end: IDENT(
+                                        "b",
+                                    ) @97..98,
+                                    inclusive: false,
+                                } @94..98,
+                            ),
+                        } @86..98,
+                    ],
+                ) @10..108,
+                ret_type: VOID,
+            } @0..108
+            expected loop after label, found LITERAL(INT { base: DECIMAL, suffix: None }) "1" at line: 1 | col: 105
+        "#]]
+    );
+}
+
+#[test]
+fn test_loops() {
+    check_parsing(
+        "fn main() { loop { break; } 'a: loop { loop { continue 'a; } break 'a; } }",
+        expect![[r#"
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        LOOP {
+                            label: None,
+                            body: BLOCK(
+                                [
+                                    BREAK(
+                                        None,
+                                    ) @19..24,
+                                ],
+                            ) @17..27,
+                        } @12..27,
+                        LOOP {
+                            label: Some(
+                                "a",
+                            ),
+                            body: BLOCK(
+                                [
+                                    LOOP {
+                                        label: None,
+                                        body: BLOCK(
+                                            [
+                                                CONTINUE(
+                                                    Some(
+                                                        "a",
+                                                    ),
+                                                ) @46..57,
+                                            ],
+                                        ) @44..60,
+                                    } @39..60,
+                                    BREAK(
+                                        Some(
+                                            "a",
+                                        ),
+                                    ) @61..69,
+                                ],
+                            ) @37..72,
+                        } @28..72,
+                    ],
+                ) @10..74,
+                ret_type: VOID,
+            } @0..74
+        "#]]
+    );
+}
+
+#[test]
+fn test_structs() {
+    check_parsing(
+        "/// A point\nstruct Point { x: i32, y: Point, } fn main() { let p = Point { x: 1, y: f(Point { x: 2 }) }; p.y.x = p.x; if p.x { 1; } }",
+        expect![[r#"
+            STRUCT {
+                name: "Point",
+                docs: [
+                    " A point",
+                ],
+                fields: [
+                    FIELD {
+                        name: "x",
+                        docs: [],
+                        ty: I32,
+                    } @27..33,
+                    FIELD {
+                        name: "y",
+                        docs: [],
+                        ty: NAMED(
+                            "Point",
+                        ),
+                    } @35..43,
+                ],
+            } @12..46
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [
+                        VAL {
+                            name: "p",
+                            ty: None,
+                            imm: false,
+                            expr: Some(
+                                STRUCT_LIT {
+                                    name: "Point",
+                                    fields: [
+                                        FIELD_INIT {
+                                            name: "x",
+                                            value: INTEGER {
+                                                val: 1,
+                                                suffix: None,
+                                            } @78..79,
+                                        } @75..79,
+                                        FIELD_INIT {
+                                            name: "y",
+                                            value: CALL {
+                                                callee: IDENT(
+                                                    "f",
+                                                ) @84..85,
+                                                args: [
+                                                    STRUCT_LIT {
+                                                        name: "Point",
+                                                        fields: [
+                                                            FIELD_INIT {
+                                                                name: "x",
+                                                                value: INTEGER {
+                                                                    val: 2,
+                                                                    suffix: None,
+                                                                } @97..98,
+                                                            } @94..98,
+                                                        ],
+                                                    } @86..100,
+                                                ],
+                                            } @84..101,
+                                        } @81..101,
+                                    ],
+                                } @67..103,
+                            ),
+                        } @59..103,
+                        ASSIGN {
+                            target: FIELD_ACCESS {
+                                base: FIELD_ACCESS {
+                                    base: IDENT(
+                                        "p",
+                                    ) @105..106,
+                                    field: "y",
+                                } @105..108,
+                                field: "x",
+                            } @105..110,
+                            value: FIELD_ACCESS {
+                                base: IDENT(
+                                    "p",
+                                ) @113..114,
+                                field: "x",
+                            } @113..116,
+                            op: EQUAL,
+                        } @105..116,
+                        IF {
+                            cond: FIELD_ACCESS {
+                                base: IDENT(
+                                    "p",
+                                ) @121..122,
+                                field: "x",
+                            } @121..124,
+                            then: BLOCK(
+                                [
+                                    INTEGER {
+                                        val: 1,
+                                        suffix: None,
+                                    } @127..128,
+                                ],
+                            ) @125..131,
+                            else_: None,
+                        } @118..131,
+                    ],
+                ) @57..133,
+                ret_type: VOID,
+            } @47..133
+        "#]]
+    );
+}
+
+#[test]
+fn test_documented_fields() {
+    check_parsing(
+        "struct Point {\n    /// x coord\n    x: i32,\n    y: i32,\n}",
+        expect![[r#"
+            STRUCT {
+                name: "Point",
+                docs: [],
+                fields: [
+                    FIELD {
+                        name: "x",
+                        docs: [
+                            " x coord",
+                        ],
+                        ty: I32,
+                    } @35..41,
+                    FIELD {
+                        name: "y",
+                        docs: [],
+                        ty: I32,
+                    } @47..53,
+                ],
+            } @0..56
+        "#]]
+    );
+}
+
+#[test]
+fn test_references() {
+    check_parsing(
+        "fn bump(p: &Point, q: &imm i32, r: &&i32) { *p.x = *q + a & b == 0; let s = &imm p.y; f(&x, &&y); }",
+        expect![[r#"
+            FUNCTION {
+                name: "bump",
+                docs: [],
+                arguments: [
+                    PARAM {
+                        name: "p",
+                        docs: [],
+                        ty: REF {
+                            ty: NAMED(
+                                "Point",
+                            ),
+                            imm: false,
+                        },
+                        imm: false,
+                    } @8..17,
+                    PARAM {
+                        name: "q",
+                        docs: [],
+                        ty: REF {
+                            ty: I32,
+                            imm: true,
+                        },
+                        imm: false,
+                    } @19..30,
+                    PARAM {
+                        name: "r",
+                        docs: [],
+                        ty: REF {
+                            ty: REF {
+                                ty: I32,
+                                imm: false,
+                            },
+                            imm: false,
+                        },
+                        imm: false,
+                    } @32..40,
+                ],
+                block: BLOCK(
+                    [
+                        ASSIGN {
+                            target: DEREF(
+                                FIELD_ACCESS {
+                                    base: IDENT(
+                                        "p",
+                                    ) @45..46,
+                                    field: "x",
+                                } @45..48,
+                            ) @44..48,
+                            value: BINARY {
+                                lhs: BINARY {
+                                    lhs: BINARY {
+                                        lhs: DEREF(
+                                            IDENT(
+                                                "q",
+                                            ) @52..53,
+                                        ) @51..53,
+                                        rhs: IDENT(
+                                            "a",
+                                        ) @56..57,
+                                        op: PLUS,
+                                    } @51..57,
+                                    rhs: IDENT(
+                                        "b",
+                                    ) @60..61,
+                                    op: AMPERSAND,
+                                } @51..61,
+                                rhs: INTEGER {
+                                    val: 0,
+                                    suffix: None,
+                                } @65..66,
+                                op: DEQUAL,
+                            } @51..66,
+                            op: EQUAL,
+                        } @44..66,
+                        VAL {
+                            name: "s",
+                            ty: None,
+                            imm: false,
+                            expr: Some(
+                                REF {
+                                    val: FIELD_ACCESS {
+                                        base: IDENT(
+                                            "p",
+                                        ) @81..82,
+                                        field: "y",
+                                    } @81..84,
+                                    imm: true,
+                                } @76..84,
+                            ),
+                        } @68..84,
+                        CALL {
+                            callee: IDENT(
+                                "f",
+                            ) @86..87,
+                            args: [
+                                REF {
+                                    val: IDENT(
+                                        "x",
+                                    ) @89..90,
+                                    imm: false,
+                                } @88..90,
+                                REF {
+                                    val: REF {
+                                        val: IDENT(
+                                            "y",
+                                        ) @94..95,
+                                        imm: false,
+                                    } @93..95,
+                                    imm: false,
+                                } @92..95,
+                            ],
+                        } @86..96,
+                    ],
+                ) @42..99,
+                ret_type: VOID,
+            } @0..99
+        "#]]
+    );
+}
+
+#[test]
+fn test_globals() {
+    check_parsing(
+        "/// Table size\nconst SIZE: i32 = 4 * 16; /// Where everything starts\nlet imm ORIGIN = Point { x: 0, y: -SIZE }; let counter = 0; const BAD = f(1); let alsobad: i32; fn main() { }",
+        expect![[r#"
+            CONST {
+                name: "SIZE",
+                docs: [
+                    " Table size",
+                ],
+                ty: Some(
+                    I32,
+                ),
+                expr: BINARY {
+                    lhs: INTEGER {
+                        val: 4,
+                        suffix: None,
+                    } @33..34,
+                    rhs: INTEGER {
+                        val: 16,
+                        suffix: None,
+                    } @37..39,
+                    op: STAR,
+                } @33..39,
+            } @15..39
+            GLOBAL {
+                name: "ORIGIN",
+                docs: [
+                    " Where everything starts",
+                ],
+                ty: None,
+                imm: true,
+                expr: STRUCT_LIT {
+                    name: "Point",
+                    fields: [
+                        FIELD_INIT {
+                            name: "x",
+                            value: INTEGER {
+                                val: 0,
+                                suffix: None,
+                            } @97..98,
+                        } @94..98,
+                        FIELD_INIT {
+                            name: "y",
+                            value: UNARY {
+                                val: IDENT(
+                                    "SIZE",
+                                ) @104..108,
+                                op: MINUS,
+                            } @103..108,
+                        } @100..108,
+                    ],
+                } @86..110,
+            } @69..110
+            GLOBAL {
+                name: "counter",
+                docs: [],
+                ty: None,
+                imm: false,
+                expr: INTEGER {
+                    val: 0,
+                    suffix: None,
+                } @126..127,
+            } @112..127
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [],
+                block: BLOCK(
+                    [],
+                ) @175..178,
+                ret_type: VOID,
+            } @165..178
+            global initializer is not a constant expression, found IDENT "f" at line: 3 | col: 73
+            expected EQUAL, found SEMICOLON ";" at line: 3 | col: 95
+        "#]]
+    );
+}
+
+#[test]
+fn test_arrays() {
+    check_parsing(
+        "const TABLE: [i32; 4] = [1, 2, 3, 4,]; fn main(grid: [[f32; SIZE]; 2]) { let z = [0; 16]; z[i + 1] = TABLE[0] * z.len; []; [x]; let local: [i32; n]; }",
+        expect![[r#"
+            CONST {
+                name: "TABLE",
+                docs: [],
+                ty: Some(
+                    ARRAY {
+                        ty: I32,
+                        len: INTEGER {
+                            val: 4,
+                            suffix: None,
+                        } @19..20,
+                    },
+                ),
+                expr: ARRAY(
+                    [
+                        INTEGER {
+                            val: 1,
+                            suffix: None,
+                        } @25..26,
+                        INTEGER {
+                            val: 2,
+                            suffix: None,
+                        } @28..29,
+                        INTEGER {
+                            val: 3,
+                            suffix: None,
+                        } @31..32,
+                        INTEGER {
+                            val: 4,
+                            suffix: None,
+                        } @34..35,
+                    ],
+                ) @24..37,
+            } @0..37
+            FUNCTION {
+                name: "main",
+                docs: [],
+                arguments: [
+                    PARAM {
+                        name: "grid",
+                        docs: [],
+                        ty: ARRAY {
+                            ty: ARRAY {
+                                ty: F32,
+                                len: IDENT(
+                                    "SIZE",
+                                ) @60..64,
+                            },
+                            len: INTEGER {
+                                val: 2,
+                                suffix: None,
+                            } @67..68,
+                        },
+                        imm: false,
+                    } @47..69,
+                ],
+                block: BLOCK(
+                    [
+                        VAL {
+                            name: "z",
+                            ty: None,
+                            imm: false,
+                            expr: Some(
+                                ARRAY_REPEAT {
+                                    value: INTEGER {
+                                        val: 0,
+                                        suffix: None,
+                                    } @82..83,
+                                    count: INTEGER {
+                                        val: 16,
+                                        suffix: None,
+                                    } @85..87,
+                                } @81..88,
+                            ),
+                        } @73..88,
+                        ASSIGN {
+                            target: INDEX {
+                                base: IDENT(
+                                    "z",
+                                ) @90..91,
+                                index: BINARY {
+                                    lhs: IDENT(
+                                        "i",
+                                    ) @92..93,
+                                    rhs: INTEGER {
+                                        val: 1,
+                                        suffix: None,
+                                    } @96..97,
+                                    op: PLUS,
+                                } @92..97,
+                            } @90..98,
+                            value: BINARY {
+                                lhs: INDEX {
+                                    base: IDENT(
+                                        "TABLE",
+                                    ) @101..106,
+                                    index: INTEGER {
+                                        val: 0,
+                                        suffix: None,
+                                    } @107..108,
+                                } @101..109,
+                                rhs: FIELD_ACCESS {
+                                    base: IDENT(
+                                        "z",
+                                    ) @112..113,
+                                    field: "len",
+                                } @112..117,
+                                op: STAR,
+                            } @101..117,
+                            op: EQUAL,
+                        } @90..117,
+                        ARRAY(
+                            [],
+                        ) @119..121,
+                        ARRAY(
+                            [
+                                IDENT(
+                                    "x",
+                                ) @124..125,
+                            ],
+                        ) @123..126,
+                        VAL {
+                            name: "local",
+                            ty: Some(
+                                ARRAY {
+                                    ty: I32,
+                                    len: IDENT(
+                                        "n",
+                                    ) @145..146,
+                                },
+                            ),
+                            imm: false,
+                            expr: None,
+                        } @128..147,
+                    ],
+                ) @71..150,
+                ret_type: VOID,
+            } @39..150
+        "#]]
+    );
+}
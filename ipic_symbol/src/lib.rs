@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+#[cfg(test)]
+mod tests;
+
+// handle to a string stored in the global interner, equal strings share one symbol
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub fn intern(text: &str) -> Self {
+        interner().lock().unwrap().intern(text)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        interner().lock().unwrap().get(*self)
+    }
+
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+}
+
+impl std::fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Default)]
+struct Interner {
+    symbols: HashMap<&'static str, Symbol>,
+    strings: Vec<&'static str>
+}
+
+impl Interner {
+    fn intern(&mut self, text: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(text) {
+            return symbol;
+        }
+
+        // interned strings live until the end of the program
+        let text: &'static str = Box::leak(text.into());
+        let symbol = Symbol(self.strings.len() as u32);
+        self.strings.push(text);
+        self.symbols.insert(text, symbol);
+
+        symbol
+    }
+
+    fn get(&self, symbol: Symbol) -> &'static str {
+        self.strings[symbol.0 as usize]
+    }
+}
+
+fn interner() -> &'static Mutex<Interner> {
+    static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(Default::default)
+}
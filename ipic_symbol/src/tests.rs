@@ -0,0 +1,13 @@
+use crate::Symbol;
+
+#[test]
+fn test_interning() {
+    let main = Symbol::intern("main");
+    let other = Symbol::intern("other");
+
+    assert_eq!(main, Symbol::intern("main"));
+    assert_ne!(main, other);
+    assert_eq!(main.as_str(), "main");
+    assert_eq!(other.to_string(), "other");
+    assert_eq!(format!("{:?}", Symbol::intern("")), "\"\"");
+}
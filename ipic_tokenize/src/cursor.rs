@@ -1,24 +1,45 @@
 use std::str::Chars;
+use crate::error::LexError;
+use crate::span::Span;
 
 pub struct Cursor<'a> {
+    src: &'a str,
     chars: Chars<'a>,
     prev: char,
     pub line: i32,
-    pub col: i32
+    pub col: i32,
+    pub pos: usize,
+    pub file_id: u32,
+    pub lossless: bool,
+    pub errors: Vec<LexError>
 }
 
 impl <'a> Cursor<'a> {
-    pub fn new(input: &'a str) -> Cursor<'a> {
-        Cursor { chars: input.chars(), prev: '\0', line: 1, col: 0 }
+    pub fn new(input: &'a str, file_id: u32) -> Cursor<'a> {
+        Cursor { src: input, chars: input.chars(), prev: '\0', line: 1, col: 0, pos: 0, file_id, lossless: false, errors: Vec::new() }
     }
 
     pub fn bump(&mut self) -> Option<char> {
         let res = self.chars.next()?;
         self.prev = res;
-        self.col += 1;
+        if res == '\n' {
+            self.line += 1;
+            self.col = 0;
+        } else {
+            self.col += 1;
+        }
+        self.pos += res.len_utf8();
         Some(res)
     }
 
+    pub fn slice(&self, start_byte: usize) -> &'a str {
+        &self.src[start_byte..self.pos]
+    }
+
+    pub fn span_from(&self, start_byte: usize) -> Span {
+        Span::new(self.file_id, start_byte, self.pos)
+    }
+
     pub fn is_eof(&mut self) -> bool {
         self.chars.as_str().is_empty()
     }
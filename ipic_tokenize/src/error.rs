@@ -0,0 +1,53 @@
+use crate::span::Span;
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum LexErrorKind {
+    UNDEFINED,
+    UNTERMINATED_STRING,
+    INVALID_ESCAPE,
+    UNTERMINATED_CHAR,
+    EMPTY_CHAR,
+    MULTI_CHAR,
+    EMPTY_EXPONENT,
+    UNTERMINATED_COMMENT,
+    EMPTY_INT,
+    INVALID_DIGIT,
+    INVALID_SUFFIX,
+    INT_OVERFLOW,
+    INVALID_RAW_STRING,
+    UNTERMINATED_RAW_STRING,
+    NON_ASCII_BYTE
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LexError {
+    pub kind: LexErrorKind,
+    pub start: (i32, i32), // row | column
+    pub end: (i32, i32),
+    pub span: Span,
+    pub text: String
+}
+
+impl std::fmt::Display for LexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self.kind {
+            LexErrorKind::UNDEFINED => "undefined token",
+            LexErrorKind::UNTERMINATED_STRING => "unterminated string literal",
+            LexErrorKind::INVALID_ESCAPE => "invalid escape sequence",
+            LexErrorKind::UNTERMINATED_CHAR => "unterminated character literal",
+            LexErrorKind::EMPTY_CHAR => "empty character literal",
+            LexErrorKind::MULTI_CHAR => "character literal may only contain one codepoint",
+            LexErrorKind::EMPTY_EXPONENT => "expected at least one digit in exponent",
+            LexErrorKind::UNTERMINATED_COMMENT => "unterminated block comment",
+            LexErrorKind::EMPTY_INT => "no valid digits found for number",
+            LexErrorKind::INVALID_DIGIT => "invalid digit for the base of the literal",
+            LexErrorKind::INVALID_SUFFIX => "invalid suffix for number literal",
+            LexErrorKind::INT_OVERFLOW => "integer literal is too large for its type",
+            LexErrorKind::INVALID_RAW_STRING => "expected `\"` after at most 255 `#` in raw string",
+            LexErrorKind::UNTERMINATED_RAW_STRING => "unterminated raw string literal",
+            LexErrorKind::NON_ASCII_BYTE => "non-ASCII character in byte string literal"
+        };
+
+        write!(f, "{} {:?} at line: {} | col: {}", msg, self.text, self.start.0, self.start.1)
+    }
+}
@@ -1,4 +1,7 @@
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+use ipic_symbol::Symbol;
+use crate::span::Span;
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum NumberBase {
     BINARY = 2,
     OCTAL = 8,
@@ -6,67 +9,234 @@ pub enum NumberBase {
     HEX = 16
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NumSuffix {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64
+}
+
+impl NumSuffix {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "i8" => Some(NumSuffix::I8),
+            "i16" => Some(NumSuffix::I16),
+            "i32" => Some(NumSuffix::I32),
+            "i64" => Some(NumSuffix::I64),
+            "u8" => Some(NumSuffix::U8),
+            "u16" => Some(NumSuffix::U16),
+            "u32" => Some(NumSuffix::U32),
+            "u64" => Some(NumSuffix::U64),
+            "f32" => Some(NumSuffix::F32),
+            "f64" => Some(NumSuffix::F64),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NumSuffix::I8 => "i8",
+            NumSuffix::I16 => "i16",
+            NumSuffix::I32 => "i32",
+            NumSuffix::I64 => "i64",
+            NumSuffix::U8 => "u8",
+            NumSuffix::U16 => "u16",
+            NumSuffix::U32 => "u32",
+            NumSuffix::U64 => "u64",
+            NumSuffix::F32 => "f32",
+            NumSuffix::F64 => "f64"
+        }
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, NumSuffix::F32 | NumSuffix::F64)
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(self, NumSuffix::I8 | NumSuffix::I16 | NumSuffix::I32 | NumSuffix::I64)
+    }
+
+    // largest literal the type can hold, literals are never negative
+    pub fn max_value(&self) -> u64 {
+        match self {
+            NumSuffix::I8 => i8::MAX as u64,
+            NumSuffix::I16 => i16::MAX as u64,
+            NumSuffix::I32 => i32::MAX as u64,
+            NumSuffix::I64 => i64::MAX as u64,
+            NumSuffix::U8 => u8::MAX as u64,
+            NumSuffix::U16 => u16::MAX as u64,
+            NumSuffix::U32 => u32::MAX as u64,
+            NumSuffix::U64 | NumSuffix::F32 | NumSuffix::F64 => u64::MAX
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LiteralKind {
-    INT{base: NumberBase, val: String},
-    STRING(String)
+    INT{base: NumberBase, suffix: Option<NumSuffix>},
+    FLOAT{suffix: Option<NumSuffix>},
+    CHAR(char),
+    BOOL(bool),
+    STRING,
+    RAW_STRING{hashes: u8},
+    BYTE_STRING
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+impl LiteralKind {
+    // digits of a number literal without the base prefix, the type suffix and `_` separators
+    pub fn digits<'a>(&self, text: &'a str) -> impl Iterator<Item = char> + 'a {
+        let (prefix, suffix) = match self {
+            LiteralKind::INT { base: NumberBase::DECIMAL, suffix } => (0, *suffix),
+            LiteralKind::INT { suffix, .. } => (2, *suffix),
+            LiteralKind::FLOAT { suffix } => (0, *suffix),
+            _ => (0, None)
+        };
+
+        let end = text.len() - suffix.map_or(0, |suffix| suffix.name().len());
+        text[prefix.min(end)..end].chars().filter(|&chr| chr != '_')
+    }
+
+    pub fn int_value(&self, text: &str) -> Option<u64> {
+        let LiteralKind::INT { base, .. } = self else {
+            return None;
+        };
+
+        let radix = *base as u32;
+        self.digits(text).try_fold(0u64, |num, chr| {
+            num.checked_mul(radix as u64)?.checked_add(chr.to_digit(radix)? as u64)
+        })
+    }
+
+    pub fn float_value(&self, text: &str) -> Option<f64> {
+        self.digits(text).collect::<String>().parse::<f64>().ok()
+    }
+
+    // contents of a raw string between its delimiters, taken as is
+    pub fn raw_value<'a>(&self, text: &'a str) -> Option<&'a str> {
+        let LiteralKind::RAW_STRING { hashes } = self else {
+            return None;
+        };
+
+        let hashes = *hashes as usize;
+        let contents = text.get(hashes + 2..)?;
+        let bytes = contents.as_bytes();
+
+        // unterminated raw strings run until the end of the input
+        let end = bytes.len().saturating_sub(hashes + 1);
+        let is_closed = bytes.len() > hashes && bytes[end] == b'"' && bytes[end + 1..].iter().all(|&byte| byte == b'#');
+
+        Some(if is_closed { &contents[..end] } else { contents })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ReservedIDents {
     FUNCTION,
     STRUCT,
     LET,
+    CONST,
     VOID,
     I32,
-    F32
+    F32,
+    BOOL,
+    IF,
+    ELSE,
+    WHILE,
+    FOR,
+    IN,
+    LOOP,
+    BREAK,
+    CONTINUE
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TokenKind {
     LITERAL(LiteralKind),
-    IDENT(String),
+    IDENT,
     RESERVED(ReservedIDents),
     PLUS,
     MINUS,
     STAR,
     SLASH,
+    PERCENT,
     DECREMENT,
     INCREMENT,
     EQUAL,
+    PLUS_EQUAL,
+    MINUS_EQUAL,
+    STAR_EQUAL,
+    SLASH_EQUAL,
     DEQUAL,
+    NEQUAL,
     LT, //less than
     MT, //more than
+    LTE,
+    MTE,
+    NOT,
+    AND,
+    OR,
+    PIPE,
+    CARET,
+    SHL,
+    SHR,
     SEMICOLON,
     COLON,
+    COMMA,
     LBRACE,
     RBRACE,
     CLBRACE,
     CRBRACE,
-    DQUOTE,
-    QUOTE,
+    SLBRACE,
+    SRBRACE,
     AMPERSAND,
     DOT,
     RANGE,
+    RANGE_INCLUSIVE,
+    LABEL,
     RETURN,
     PRINT,
     PRINTLN,
     IMMUTABLE,
     ARROW,
+    DOC_COMMENT,
+    INNER_DOC_COMMENT,
+    COMMENT,
+    WHITESPACE,
+    NEWLINE,
+    UNKNOWN,
     EOF
 }
 
-#[derive(Debug, Clone)]
-pub struct Token {
+#[derive(Debug, Clone, Copy)]
+pub struct Token<'src> {
     pub kind: TokenKind,
-    pub pos: (i32, i32) // row | column
+    pub text: &'src str,
+    pub pos: (i32, i32), // row | column
+    pub span: Span,
+    // interned name of identifiers and labels
+    pub symbol: Option<Symbol>
 }
 
-impl Token {
+impl Token<'_> {
     pub fn uninited() -> Self {
         Self {
             kind: TokenKind::EOF,
-            pos: (0, 0)
+            text: "",
+            pos: (0, 0),
+            span: Span::default(),
+            symbol: None
         }
     }
+
+    // only called on identifiers and labels, which always carry one
+    pub fn symbol(&self) -> Symbol {
+        self.symbol.unwrap_or_else(|| Symbol::intern(self.text))
+    }
 }
\ No newline at end of file
@@ -1,17 +1,20 @@
+#![allow(non_camel_case_types)]
+
 mod cursor;
 pub mod grammar;
+pub mod error;
+pub mod span;
 use std::iter::Peekable;
+use ipic_symbol::Symbol;
 use self::grammar::*;
 use self::cursor::*;
+use self::error::*;
 
 #[cfg(test)]
 mod tests;
 
 pub fn is_whitespace(symbol: char) -> bool {
-    match symbol {
-        ' ' => true,
-        _ => false
-    }
+    symbol != '\n' && symbol.is_whitespace()
 }
 
 fn is_id_continue(c: char) -> bool {
@@ -22,188 +25,612 @@ fn is_id(symbol: char) -> bool {
     symbol == '_' || unicode_xid::UnicodeXID::is_xid_start(symbol)
 }
 
-impl Cursor<'_> {
-    pub fn advance_token(&mut self) -> Token {
+impl<'a> Cursor<'a> {
+    // whitespace, comments and undefined symbols reach the stream only in lossless mode,
+    // otherwise they are skipped in a loop so long runs of them don't grow the stack
+    pub fn advance_token(&mut self) -> Token<'a> {
+        loop {
+            let token = self.scan_token();
+            let is_trivia = matches!(token.kind,
+                TokenKind::WHITESPACE | TokenKind::NEWLINE | TokenKind::COMMENT | TokenKind::UNKNOWN);
+
+            if self.lossless || !is_trivia {
+                return token;
+            }
+        }
+    }
+
+    fn scan_token(&mut self) -> Token<'a> {
+        let start = self.pos;
+        let pos = (self.line, self.col + 1);
         let first = match self.bump() {
             Some(symbol) => symbol,
-            None => return Token{kind: TokenKind::EOF, pos:(self.line, self.col)}
+            None => return self.token(TokenKind::EOF, pos, start)
         };
 
+        if first == '\r' && self.first() == '\n' {
+            self.bump();
+            return self.token(TokenKind::NEWLINE, pos, start);
+        }
+
         if is_whitespace(first) {
             self.skip_whitespace();
-            return self.advance_token();
+            return self.token(TokenKind::WHITESPACE, pos, start);
         }
 
         if first == '\n' {
-            self.line += 1;
-            return self.advance_token();
+            return self.token(TokenKind::NEWLINE, pos, start);
         }
 
-        if first == '/' {
-            if self.first() == '/' {
-                self.skip_comment_line(); 
-                return self.advance_token();
-            }
+        if first == '/' && self.first() == '/' {
+            return match self.parse_comment_line() {
+                Some(kind) => self.token(kind, pos, start),
+                None => self.token(TokenKind::COMMENT, pos, start)
+            };
+        }
+
+        if first == '/' && self.first() == '*' {
+            self.skip_comment_block(pos);
+            return self.token(TokenKind::COMMENT, pos, start);
         }
 
         let kind = match first {
             '+' => match self.first() {
                 '+' => { self.bump(); TokenKind::INCREMENT },
+                '=' => { self.bump(); TokenKind::PLUS_EQUAL },
                 _ => TokenKind::PLUS
             },
             '-' => match self.first() {
                 '-' => { self.bump(); TokenKind::DECREMENT },
                 '>' => { self.bump(); TokenKind::ARROW }
+                '=' => { self.bump(); TokenKind::MINUS_EQUAL },
                 _ => TokenKind::MINUS
             },
-            '/' => TokenKind::SLASH,
+            '/' => match self.first() {
+                '=' => { self.bump(); TokenKind::SLASH_EQUAL },
+                _ => TokenKind::SLASH
+            },
+            '*' => match self.first() {
+                '=' => { self.bump(); TokenKind::STAR_EQUAL },
+                _ => TokenKind::STAR
+            },
             '=' => match self.first() {
                 '=' => { self.bump(); TokenKind::DEQUAL },
                 _ => TokenKind::EQUAL
             },
             '.' => match self.first() {
-                '.' => { self.bump(); TokenKind::RANGE }
+                '.' => match self.second() {
+                    '=' => { self.bump(); self.bump(); TokenKind::RANGE_INCLUSIVE }
+                    _ => { self.bump(); TokenKind::RANGE }
+                },
                 _ => TokenKind::DOT
             },
-            '>' => TokenKind::MT,
-            '<' => TokenKind::LT,
-            '*' => TokenKind::STAR,
+            '!' => match self.first() {
+                '=' => { self.bump(); TokenKind::NEQUAL },
+                _ => TokenKind::NOT
+            },
+            '>' => match self.first() {
+                '=' => { self.bump(); TokenKind::MTE },
+                '>' => { self.bump(); TokenKind::SHR },
+                _ => TokenKind::MT
+            },
+            '<' => match self.first() {
+                '=' => { self.bump(); TokenKind::LTE },
+                '<' => { self.bump(); TokenKind::SHL },
+                _ => TokenKind::LT
+            },
+            '&' => match self.first() {
+                '&' => { self.bump(); TokenKind::AND },
+                _ => TokenKind::AMPERSAND
+            },
+            '|' => match self.first() {
+                '|' => { self.bump(); TokenKind::OR },
+                _ => TokenKind::PIPE
+            },
+            '^' => TokenKind::CARET,
+            '%' => TokenKind::PERCENT,
             ':' => TokenKind::COLON,
+            ',' => TokenKind::COMMA,
             ';' => TokenKind::SEMICOLON,
             '(' => TokenKind::LBRACE,
             ')' => TokenKind::RBRACE,
             '{' => TokenKind::CLBRACE,
             '}' => TokenKind::CRBRACE,
-            '"' => TokenKind::DQUOTE,
-            '\'' => TokenKind::QUOTE,
-            '&' => TokenKind::AMPERSAND,
+            '[' => TokenKind::SLBRACE,
+            ']' => TokenKind::SRBRACE,
+            '"' => self.parse_string(pos, start, false),
+            'b' if self.first() == '"' => {
+                self.bump();
+                self.parse_string(pos, start, true)
+            }
+            'r' if matches!(self.first(), '"' | '#') => match self.parse_raw_string(pos, start) {
+                Some(kind) => kind,
+                None => return self.token(TokenKind::UNKNOWN, pos, start)
+            },
+            '\'' => self.parse_char(),
             first @ '0'..='9' => self.parse_num(first),
-            first if is_id(first) => self.parse_id(first),
+            first if is_id(first) => self.parse_id(start),
+
+            _ => {
+                self.error(LexErrorKind::UNDEFINED, pos, first.to_string());
+                return self.token(TokenKind::UNKNOWN, pos, start);
+            }
+        };
 
-            _ => panic!("undefined token at line: {} | col: {}", self.line, self.col)
+        self.token(kind, pos, start)
+    }
+
+    // names are interned once here, labels without their leading `'`
+    fn token(&self, kind: TokenKind, pos: (i32, i32), start: usize) -> Token<'a> {
+        let text = self.slice(start);
+        let symbol = match kind {
+            TokenKind::IDENT => Some(Symbol::intern(text)),
+            TokenKind::LABEL => Some(Symbol::intern(&text[1..])),
+            _ => None
         };
 
-        Token{kind, pos:(self.line, self.col)}
+        Token{kind, text, pos, span: self.span_from(start), symbol}
+    }
+
+    fn error(&mut self, kind: LexErrorKind, start: (i32, i32), text: String) {
+        let span = self.span_from(self.pos - text.len());
+        self.errors.push(LexError { kind, start, end: (self.line, self.col), span, text });
+    }
+
+    // byte strings take the same escapes, but every char has to fit into a byte
+    fn parse_string(&mut self, start: (i32, i32), start_byte: usize, is_byte: bool) -> TokenKind {
+        loop {
+            let pos = (self.line, self.col + 1);
+            let chr_start = self.pos;
+            let chr = match self.bump() {
+                Some('"') => break,
+                Some('\\') => self.parse_escape(),
+                Some(chr) => Some(chr),
+                None => {
+                    let text = self.slice(start_byte).to_string();
+                    self.error(LexErrorKind::UNTERMINATED_STRING, start, text);
+                    break;
+                }
+            };
+
+            if is_byte && chr.is_some_and(|chr| !chr.is_ascii()) {
+                let text = self.slice(chr_start).to_string();
+                self.error(LexErrorKind::NON_ASCII_BYTE, pos, text);
+            }
+        }
+
+        if is_byte {
+            TokenKind::LITERAL(LiteralKind::BYTE_STRING)
+        } else {
+            TokenKind::LITERAL(LiteralKind::STRING)
+        }
     }
 
-    fn parse_id(&mut self, symbol: char) -> TokenKind {
-        let first = symbol;
-        let mut result = String::from(first);
+    // `r#"..."#`, the closing quote has to be followed by as many `#` as the opening one
+    fn parse_raw_string(&mut self, start: (i32, i32), start_byte: usize) -> Option<TokenKind> {
+        let mut hashes = 0usize;
+        while self.first() == '#' {
+            self.bump();
+            hashes += 1;
+        }
+
+        if self.first() != '"' || hashes > u8::MAX as usize {
+            let text = self.slice(start_byte).to_string();
+            self.error(LexErrorKind::INVALID_RAW_STRING, start, text);
+            return None;
+        }
+        self.bump();
 
         loop {
-            let chr = self.first();
+            match self.bump() {
+                Some('"') => {
+                    let mut closing = 0;
+                    while closing < hashes && self.first() == '#' {
+                        self.bump();
+                        closing += 1;
+                    }
+
+                    if closing == hashes {
+                        break;
+                    }
+                }
+                Some(_) => (),
+                None => {
+                    let text = self.slice(start_byte).to_string();
+                    self.error(LexErrorKind::UNTERMINATED_RAW_STRING, start, text);
+                    break;
+                }
+            }
+        }
+
+        Some(TokenKind::LITERAL(LiteralKind::RAW_STRING { hashes: hashes as u8 }))
+    }
 
-            if !is_id_continue(chr) {
-                break;
+    fn parse_char(&mut self) -> TokenKind {
+        let start = (self.line, self.col);
+        let start_byte = self.pos - 1;
+
+        // `'a` or `'outer` without a closing quote is a loop label
+        if is_id(self.first()) && self.second() != '\'' {
+            let first = self.first();
+            self.eat_while(is_id_continue);
+            if self.first() != '\'' {
+                return TokenKind::LABEL;
             }
 
             self.bump();
+            let text = self.slice(start_byte).to_string();
+            self.error(LexErrorKind::MULTI_CHAR, start, text);
+            return TokenKind::LITERAL(LiteralKind::CHAR(first));
+        }
+        let mut result = None;
+        let mut count = 0;
+
+        loop {
+            if self.is_eof() || self.first() == '\n' {
+                let text = self.slice(start_byte).to_string();
+                self.error(LexErrorKind::UNTERMINATED_CHAR, start, text);
+                return TokenKind::LITERAL(LiteralKind::CHAR(result.unwrap_or('\0')));
+            }
+
+            let chr = match self.bump().unwrap() {
+                '\'' => break,
+                '\\' => self.parse_escape(),
+                chr => Some(chr)
+            };
 
-            result.push(chr);
+            count += 1;
+            result = result.or(chr);
         }
-        
-        match result.as_str() {
+
+        let text = self.slice(start_byte).to_string();
+        match count {
+            0 => self.error(LexErrorKind::EMPTY_CHAR, start, text),
+            1 => (),
+            _ => self.error(LexErrorKind::MULTI_CHAR, start, text)
+        };
+
+        TokenKind::LITERAL(LiteralKind::CHAR(result.unwrap_or('\0')))
+    }
+
+    fn parse_escape(&mut self) -> Option<char> {
+        let start = (self.line, self.col);
+
+        let chr = self.bump()?;
+
+        match chr {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            '0' => Some('\0'),
+            '\\' => Some('\\'),
+            '"' => Some('"'),
+            '\'' => Some('\''),
+            'u' => self.parse_unicode_escape(start),
+            _ => {
+                self.error(LexErrorKind::INVALID_ESCAPE, start, format!("\\{}", chr));
+                None
+            }
+        }
+    }
+
+    fn parse_unicode_escape(&mut self, start: (i32, i32)) -> Option<char> {
+        let start_byte = self.pos - 2;
+
+        if self.first() != '{' {
+            self.error(LexErrorKind::INVALID_ESCAPE, start, self.slice(start_byte).to_string());
+            return None;
+        }
+        self.bump();
+
+        let digits_start = self.pos;
+        self.eat_while(|chr| chr.is_ascii_hexdigit());
+        let digits = self.slice(digits_start);
+
+        if self.first() != '}' {
+            self.error(LexErrorKind::INVALID_ESCAPE, start, self.slice(start_byte).to_string());
+            return None;
+        }
+        self.bump();
+
+        let chr = match digits.len() {
+            1..=6 => u32::from_str_radix(digits, 16).ok().and_then(char::from_u32),
+            _ => None
+        };
+
+        if chr.is_none() {
+            self.error(LexErrorKind::INVALID_ESCAPE, start, self.slice(start_byte).to_string());
+        }
+
+        chr
+    }
+
+    fn parse_id(&mut self, start: usize) -> TokenKind {
+        self.eat_while(is_id_continue);
+
+        match self.slice(start) {
             "let" => TokenKind::RESERVED(ReservedIDents::LET),
+            "const" => TokenKind::RESERVED(ReservedIDents::CONST),
             "struct" => TokenKind::RESERVED(ReservedIDents::STRUCT),
             "return" => TokenKind::RETURN,
             "print" => TokenKind::PRINT,
             "println" => TokenKind::PRINTLN,
             "imm" => TokenKind::IMMUTABLE,
             "fn" => TokenKind::RESERVED(ReservedIDents::FUNCTION),
-            _ => TokenKind::IDENT(result)
+            "if" => TokenKind::RESERVED(ReservedIDents::IF),
+            "else" => TokenKind::RESERVED(ReservedIDents::ELSE),
+            "while" => TokenKind::RESERVED(ReservedIDents::WHILE),
+            "for" => TokenKind::RESERVED(ReservedIDents::FOR),
+            "in" => TokenKind::RESERVED(ReservedIDents::IN),
+            "loop" => TokenKind::RESERVED(ReservedIDents::LOOP),
+            "break" => TokenKind::RESERVED(ReservedIDents::BREAK),
+            "continue" => TokenKind::RESERVED(ReservedIDents::CONTINUE),
+            "void" => TokenKind::RESERVED(ReservedIDents::VOID),
+            "i32" => TokenKind::RESERVED(ReservedIDents::I32),
+            "f32" => TokenKind::RESERVED(ReservedIDents::F32),
+            "bool" => TokenKind::RESERVED(ReservedIDents::BOOL),
+            "true" => TokenKind::LITERAL(LiteralKind::BOOL(true)),
+            "false" => TokenKind::LITERAL(LiteralKind::BOOL(false)),
+            _ => TokenKind::IDENT
         }
     }
 
     fn skip_comment_line(&mut self) {
-        self.eat_while(|c| c != '\n');
+        while !self.is_eof() && !self.at_newline() {
+            self.bump();
+        }
     }
 
-    fn skip_whitespace(&mut self) {
-        self.eat_while(is_whitespace);
+    fn at_newline(&mut self) -> bool {
+        self.first() == '\n' || (self.first() == '\r' && self.second() == '\n')
     }
 
-    fn parse_num(&mut self, first: char) -> TokenKind {
-        let mut base = NumberBase::DECIMAL;
-        if first == '0' {
-            let lit_kind = match self.first() {
-                'x' => {
-                    base = NumberBase::HEX;
+    // `///` documents the next item, `//!` the enclosing one, `////` is a plain comment
+    fn parse_comment_line(&mut self) -> Option<TokenKind> {
+        self.bump();
+
+        let is_inner = match (self.first(), self.second()) {
+            ('/', '/') => None,
+            ('/', _) => Some(false),
+            ('!', _) => Some(true),
+            _ => None
+        };
+
+        let Some(is_inner) = is_inner else {
+            self.skip_comment_line();
+            return None;
+        };
+
+        self.skip_comment_line();
+
+        if is_inner {
+            Some(TokenKind::INNER_DOC_COMMENT)
+        } else {
+            Some(TokenKind::DOC_COMMENT)
+        }
+    }
+
+    fn skip_comment_block(&mut self, start: (i32, i32)) {
+        let start_byte = self.pos - 1;
+        let mut depth = 1;
+        self.bump();
+
+        while depth > 0 {
+            match self.bump() {
+                Some('/') if self.first() == '*' => {
                     self.bump();
-                    LiteralKind::INT { base, val: self.parse_hex_num_to_str() }
+                    depth += 1;
                 }
-                'b' => {
-                    base = NumberBase::BINARY;
+                Some('*') if self.first() == '/' => {
                     self.bump();
-                    LiteralKind::INT { base, val: self.parse_num_to_str() }
+                    depth -= 1;
                 }
-                'o' => {
-                    base = NumberBase::OCTAL;
-                    self.bump();
-                    LiteralKind::INT { base, val: self.parse_num_to_str() }
+                Some(_) => (),
+                None => {
+                    let text = self.slice(start_byte).to_string();
+                    self.error(LexErrorKind::UNTERMINATED_COMMENT, start, text);
+                    break;
                 }
-                '0'..='9' | '_' => LiteralKind::INT { base: base, val: self.parse_num_to_str() },
-                _ => LiteralKind::INT { base, val: "0".to_string() }
-            };
+            }
+        }
+    }
 
-            TokenKind::LITERAL(lit_kind)
-        } else {
-            let conv = first.to_string();
-            TokenKind::LITERAL(LiteralKind::INT { base, val: (conv + &self.parse_num_to_str()) })
+    fn skip_whitespace(&mut self) {
+        while is_whitespace(self.first()) && !self.at_newline() {
+            self.bump();
         }
     }
 
-    fn parse_num_to_str(&mut self) -> String {
-        let mut result = String::new();
+    fn parse_num(&mut self, first: char) -> TokenKind {
+        let start = (self.line, self.col);
+        let start_byte = self.pos - 1;
+
+        let base = match (first, self.first()) {
+            ('0', 'x') => NumberBase::HEX,
+            ('0', 'b') => NumberBase::BINARY,
+            ('0', 'o') => NumberBase::OCTAL,
+            _ => NumberBase::DECIMAL
+        };
 
-        loop {
-            let chr = self.first();
-            match chr {
-                '_' => _ = self.bump(),
-                '0'..='9' => result.push(self.bump().unwrap()),
-                _ => break,
-            };
+        let is_float = match base {
+            NumberBase::DECIMAL => self.parse_decimal(),
+            NumberBase::HEX => {
+                self.bump();
+                self.eat_while(|chr| chr.is_ascii_hexdigit() || chr == '_');
+                false
+            }
+            _ => {
+                self.bump();
+                self.eat_while(|chr| chr.is_ascii_digit() || chr == '_');
+                false
+            }
         };
 
-        return result;
+        let suffix_start = self.pos;
+        if is_id(self.first()) {
+            self.eat_while(is_id_continue);
+        }
+        let suffix = self.slice(suffix_start);
+
+        TokenKind::LITERAL(self.validate_num(base, is_float, suffix, start, start_byte))
     }
 
-    fn parse_hex_num_to_str(&mut self) -> String {
-        let mut result = String::new();
+    fn validate_num(&mut self, base: NumberBase, is_float: bool, suffix: &str, start: (i32, i32), start_byte: usize) -> LiteralKind {
+        let text = self.slice(start_byte);
+
+        let suffix = match suffix {
+            "" => None,
+            name => match NumSuffix::from_name(name) {
+                Some(suffix) => Some(suffix),
+                None => {
+                    self.error(LexErrorKind::INVALID_SUFFIX, start, text.to_string());
+                    return if is_float {
+                        LiteralKind::FLOAT { suffix: None }
+                    } else {
+                        LiteralKind::INT { base, suffix: None }
+                    };
+                }
+            }
+        };
 
-        loop {
-            let chr = self.first();
-            match chr {
-                '_' => _ = self.bump(),
-                '0'..='9' | 'a'..='f' | 'A'..='F' => result.push(self.bump().unwrap()),
-                _ => break,
-            };
+        if is_float {
+            if suffix.is_some_and(|suffix| !suffix.is_float()) {
+                self.error(LexErrorKind::INVALID_SUFFIX, start, text.to_string());
+                return LiteralKind::FLOAT { suffix: None };
+            }
+            return LiteralKind::FLOAT { suffix };
+        }
+
+        let literal = LiteralKind::INT { base, suffix };
+        let mut digits = literal.digits(text).peekable();
+
+        if digits.peek().is_none() {
+            self.error(LexErrorKind::EMPTY_INT, start, text.to_string());
+            return literal;
+        }
+
+        if digits.any(|chr| !chr.is_digit(base as u32)) {
+            self.error(LexErrorKind::INVALID_DIGIT, start, text.to_string());
+            return literal;
+        }
+
+        if let Some(suffix) = suffix.filter(|suffix| suffix.is_float()) {
+            if base != NumberBase::DECIMAL {
+                self.error(LexErrorKind::INVALID_SUFFIX, start, text.to_string());
+                return LiteralKind::INT { base, suffix: None };
+            }
+            return LiteralKind::FLOAT { suffix: Some(suffix) };
+        }
+
+        // signed literals can go one past the max since they may be negated,
+        // whether they are is only known to the parser
+        let suffix = suffix.unwrap_or(NumSuffix::I32);
+        let max = suffix.max_value() + suffix.is_signed() as u64;
+        match literal.int_value(text) {
+            Some(num) if num <= max => (),
+            _ => self.error(LexErrorKind::INT_OVERFLOW, start, text.to_string())
         };
 
-        return result;
+        literal
+    }
+
+    // eats the rest of a decimal literal, returns whether it has a fraction or an exponent
+    fn parse_decimal(&mut self) -> bool {
+        self.eat_decimal_digits();
+        let mut is_float = false;
+
+        if self.first() == '.' && self.second().is_ascii_digit() {
+            is_float = true;
+            self.bump();
+            self.eat_decimal_digits();
+        }
+
+        if matches!(self.first(), 'e' | 'E') && matches!(self.second(), '0'..='9' | '+' | '-' | '_') {
+            is_float = true;
+            let start = (self.line, self.col + 1);
+            let start_byte = self.pos;
+            self.bump();
+
+            if matches!(self.first(), '+' | '-') {
+                self.bump();
+            }
+
+            let digits_start = self.pos;
+            self.eat_decimal_digits();
+            if !self.slice(digits_start).chars().any(|chr| chr.is_ascii_digit()) {
+                let text = self.slice(start_byte).to_string();
+                self.error(LexErrorKind::EMPTY_EXPONENT, start, text);
+            }
+        }
+
+        is_float
+    }
+
+    fn eat_decimal_digits(&mut self) {
+        self.eat_while(|chr| chr.is_ascii_digit() || chr == '_');
     }
 }
 
 pub struct TokenIterator<'a> {
-    pub toks: Peekable<Box<dyn Iterator<Item = Token> + 'a>>
+    pub toks: Peekable<std::vec::IntoIter<Token<'a>>>,
+    pub eof: Token<'a>
 }
 
 impl<'a> TokenIterator<'a> {
-    fn new(tokens: Box<dyn Iterator<Item = Token> + 'a>) -> Self {
-        TokenIterator { toks: tokens.peekable() }
+    fn new(tokens: Vec<Token<'a>>, eof: Token<'a>) -> Self {
+        TokenIterator { toks: tokens.into_iter().peekable(), eof }
     }
 }
 
-pub fn tokenize(input: &str) -> TokenIterator {
-    let mut cursor = Cursor::new(input);
+pub fn tokenize(input: &str) -> (TokenIterator<'_>, Vec<LexError>) {
+    tokenize_file(input, 0)
+}
+
+pub fn tokenize_file(input: &str, file_id: u32) -> (TokenIterator<'_>, Vec<LexError>) {
+    collect_tokens(Cursor::new(input, file_id))
+}
 
-    let iterator = std::iter::from_fn(move || {
+// every byte of the input ends up in exactly one token, including whitespace and comments
+pub fn tokenize_lossless(input: &str, file_id: u32) -> (TokenIterator<'_>, Vec<LexError>) {
+    let mut cursor = Cursor::new(input, file_id);
+    cursor.lossless = true;
+
+    collect_tokens(cursor)
+}
+
+fn collect_tokens(mut cursor: Cursor<'_>) -> (TokenIterator<'_>, Vec<LexError>) {
+    let mut tokens = Vec::new();
+    let eof = loop {
         let token = cursor.advance_token();
-        if token.kind != TokenKind::EOF {Some(token)} else {None}
-    }).peekable();
+        if token.kind == TokenKind::EOF {
+            break token;
+        }
+        tokens.push(token);
+    };
 
-    TokenIterator::new(Box::new(iterator))
+    (TokenIterator::new(tokens, eof), cursor.errors)
 }
 
+// value of a string literal token, escapes are already reported by the lexer
+pub fn unescape(text: &str) -> String {
+    let raw = text.strip_prefix('"').unwrap_or(text);
+    let raw = raw.strip_suffix('"').unwrap_or(raw);
 
+    let mut cursor = Cursor::new(raw, 0);
+    let mut result = String::with_capacity(raw.len());
 
+    while let Some(chr) = cursor.bump() {
+        match chr {
+            '\\' => result.extend(cursor.parse_escape()),
+            '\r' if cursor.first() == '\n' => (),
+            chr => result.push(chr)
+        }
+    }
+
+    result
+}
 
+// value of a byte string literal token, non-ASCII chars are already reported by the lexer
+pub fn unescape_bytes(text: &str) -> Vec<u8> {
+    unescape(text.strip_prefix('b').unwrap_or(text)).into_bytes()
+}
@@ -0,0 +1,51 @@
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Span {
+    pub file_id: u32,
+    pub start_byte: usize,
+    pub end_byte: usize
+}
+
+impl Span {
+    pub fn new(file_id: u32, start_byte: usize, end_byte: usize) -> Self {
+        Self { file_id, start_byte, end_byte }
+    }
+
+    pub fn to(self, end: Span) -> Span {
+        Span::new(self.file_id, self.start_byte.min(end.start_byte), self.end_byte.max(end.end_byte))
+    }
+
+    pub fn len(&self) -> usize {
+        self.end_byte - self.start_byte
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start_byte == self.end_byte
+    }
+}
+
+pub struct LineIndex<'a> {
+    src: &'a str,
+    line_starts: Vec<usize>
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(src: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(src.match_indices('\n').map(|(idx, _)| idx + 1));
+
+        Self { src, line_starts }
+    }
+
+    // row | column of the byte, both starting from 1
+    pub fn line_col(&self, byte: usize) -> (i32, i32) {
+        let line = self.line_starts.partition_point(|&start| start <= byte) - 1;
+        let line_start = self.line_starts[line];
+        let col = self.src[line_start..byte.min(self.src.len())].chars().count();
+
+        (line as i32 + 1, col as i32 + 1)
+    }
+
+    pub fn span_pos(&self, span: Span) -> ((i32, i32), (i32, i32)) {
+        (self.line_col(span.start_byte), self.line_col(span.end_byte))
+    }
+}
@@ -2,27 +2,56 @@ use super::*;
 use expect_test::{expect, Expect};
 
 fn check_lexing(src: &str, expect: Expect) {
-    let lexed: String = tokenize(src).toks
-    .map(|token| {format!("{:?}\n", token.kind)}).collect();
+    let (tokens, errors) = tokenize(src);
+    let mut lexed: String = tokens.toks
+    .map(|token| match token.kind {
+        TokenKind::IDENT | TokenKind::LABEL | TokenKind::LITERAL(_) | TokenKind::DOC_COMMENT | TokenKind::INNER_DOC_COMMENT =>
+            format!("{:?} {:?}\n", token.kind, token.text),
+        _ => format!("{:?}\n", token.kind)
+    }).collect();
+
+    for error in errors {
+        lexed += &format!("{:?}\n", error);
+    }
 
     expect.assert_eq(&lexed);
 }
 
 #[test]
 fn test_lexer() {
-    check_lexing("let struct return print println imm fn ", 
+    check_lexing("let const struct return print println imm fn ",
     expect![[r#"
-        LET
-        STRUCT
+        RESERVED(LET)
+        RESERVED(CONST)
+        RESERVED(STRUCT)
         RETURN
         PRINT
         PRINTLN
         IMMUTABLE
-        FUNCTION
+        RESERVED(FUNCTION)
+    "#]]);
+
+    check_lexing("if else while for in loop break continue true false void i32 f32 bool iffy ",
+    expect![[r#"
+        RESERVED(IF)
+        RESERVED(ELSE)
+        RESERVED(WHILE)
+        RESERVED(FOR)
+        RESERVED(IN)
+        RESERVED(LOOP)
+        RESERVED(BREAK)
+        RESERVED(CONTINUE)
+        LITERAL(BOOL(true)) "true"
+        LITERAL(BOOL(false)) "false"
+        RESERVED(VOID)
+        RESERVED(I32)
+        RESERVED(F32)
+        RESERVED(BOOL)
+        IDENT "iffy"
     "#]]);
-    
+
     check_lexing(
-        "+ - / * . .. -> : ; ",
+        "+ - / * . .. -> : ; ( ) { } [ ] ",
         expect![[r#"
             PLUS
             MINUS
@@ -33,6 +62,369 @@ fn test_lexer() {
             ARROW
             COLON
             SEMICOLON
+            LBRACE
+            RBRACE
+            CLBRACE
+            CRBRACE
+            SLBRACE
+            SRBRACE
+        "#]]
+    );
+}
+
+#[test]
+fn test_lex_errors() {
+    check_lexing(
+        "let a = 1 $ 2;\n@",
+        expect![[r#"
+            RESERVED(LET)
+            IDENT "a"
+            EQUAL
+            LITERAL(INT { base: DECIMAL, suffix: None }) "1"
+            LITERAL(INT { base: DECIMAL, suffix: None }) "2"
+            SEMICOLON
+            LexError { kind: UNDEFINED, start: (1, 11), end: (1, 11), span: Span { file_id: 0, start_byte: 10, end_byte: 11 }, text: "$" }
+            LexError { kind: UNDEFINED, start: (2, 1), end: (2, 1), span: Span { file_id: 0, start_byte: 15, end_byte: 16 }, text: "@" }
+        "#]]
+    );
+}
+
+#[test]
+fn test_many_undefined() {
+    let src = "$".repeat(30000);
+    let (tokens, errors) = tokenize(&src);
+    assert_eq!(tokens.toks.count(), 0);
+    assert_eq!(errors.len(), 30000);
+}
+
+#[test]
+fn test_many_comments() {
+    let src = format!("fn main() {{\n{}}}", "// c\n".repeat(100000));
+    let (tokens, errors) = tokenize(&src);
+    assert_eq!(tokens.toks.count(), 6);
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_string_literals() {
+    check_lexing(
+        r#""hello" "tab\tquote\"slash\\" "\u{48}\u{1F600}" "two
+lines""#,
+        expect![[r#"
+            LITERAL(STRING) "\"hello\""
+            LITERAL(STRING) "\"tab\\tquote\\\"slash\\\\\""
+            LITERAL(STRING) "\"\\u{48}\\u{1F600}\""
+            LITERAL(STRING) "\"two\nlines\""
+        "#]]
+    );
+
+    let values: Vec<String> = tokenize("\"tab\\tquote\\\"slash\\\\\" \"\\u{48}\\u{1F600}\" \"cr\r\nlf\"").0.toks
+    .map(|token| unescape(token.text)).collect();
+    assert_eq!(values, ["tab\tquote\"slash\\", "H😀", "cr\nlf"]);
+
+    check_lexing(
+        r#""bad \q \u{110000} \u41" ; "open"#,
+        expect![[r#"
+            LITERAL(STRING) "\"bad \\q \\u{110000} \\u41\""
+            SEMICOLON
+            LITERAL(STRING) "\"open"
+            LexError { kind: INVALID_ESCAPE, start: (1, 6), end: (1, 7), span: Span { file_id: 0, start_byte: 5, end_byte: 7 }, text: "\\q" }
+            LexError { kind: INVALID_ESCAPE, start: (1, 9), end: (1, 18), span: Span { file_id: 0, start_byte: 8, end_byte: 18 }, text: "\\u{110000}" }
+            LexError { kind: INVALID_ESCAPE, start: (1, 20), end: (1, 21), span: Span { file_id: 0, start_byte: 19, end_byte: 21 }, text: "\\u" }
+            LexError { kind: UNTERMINATED_STRING, start: (1, 28), end: (1, 32), span: Span { file_id: 0, start_byte: 27, end_byte: 32 }, text: "\"open" }
         "#]]
     );
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_number_literals() {
+    check_lexing(
+        "0 007 1_000 0x1F 0b101 0o17 1.5 0.25 1e-3 2.5E+10 1_0.0_1 0..n 1.x 1e 1e+",
+        expect![[r#"
+            LITERAL(INT { base: DECIMAL, suffix: None }) "0"
+            LITERAL(INT { base: DECIMAL, suffix: None }) "007"
+            LITERAL(INT { base: DECIMAL, suffix: None }) "1_000"
+            LITERAL(INT { base: HEX, suffix: None }) "0x1F"
+            LITERAL(INT { base: BINARY, suffix: None }) "0b101"
+            LITERAL(INT { base: OCTAL, suffix: None }) "0o17"
+            LITERAL(FLOAT { suffix: None }) "1.5"
+            LITERAL(FLOAT { suffix: None }) "0.25"
+            LITERAL(FLOAT { suffix: None }) "1e-3"
+            LITERAL(FLOAT { suffix: None }) "2.5E+10"
+            LITERAL(FLOAT { suffix: None }) "1_0.0_1"
+            LITERAL(INT { base: DECIMAL, suffix: None }) "0"
+            RANGE
+            IDENT "n"
+            LITERAL(INT { base: DECIMAL, suffix: None }) "1"
+            DOT
+            IDENT "x"
+            LITERAL(INT { base: DECIMAL, suffix: None }) "1e"
+            LITERAL(FLOAT { suffix: None }) "1e+"
+            LexError { kind: INVALID_SUFFIX, start: (1, 68), end: (1, 69), span: Span { file_id: 0, start_byte: 67, end_byte: 69 }, text: "1e" }
+            LexError { kind: EMPTY_EXPONENT, start: (1, 72), end: (1, 73), span: Span { file_id: 0, start_byte: 71, end_byte: 73 }, text: "e+" }
+        "#]]
+    );
+}
+
+#[test]
+fn test_char_literals() {
+    check_lexing(
+        r"'a' '\n' '\'' '\u{263A}' 'ж' '' 'ab' '\q' '1",
+        expect![[r#"
+            LITERAL(CHAR('a')) "'a'"
+            LITERAL(CHAR('\n')) "'\\n'"
+            LITERAL(CHAR('\'')) "'\\''"
+            LITERAL(CHAR('☺')) "'\\u{263A}'"
+            LITERAL(CHAR('ж')) "'ж'"
+            LITERAL(CHAR('\0')) "''"
+            LITERAL(CHAR('a')) "'ab'"
+            LITERAL(CHAR('\0')) "'\\q'"
+            LITERAL(CHAR('1')) "'1"
+            LexError { kind: EMPTY_CHAR, start: (1, 30), end: (1, 31), span: Span { file_id: 0, start_byte: 30, end_byte: 32 }, text: "''" }
+            LexError { kind: MULTI_CHAR, start: (1, 33), end: (1, 36), span: Span { file_id: 0, start_byte: 33, end_byte: 37 }, text: "'ab'" }
+            LexError { kind: INVALID_ESCAPE, start: (1, 39), end: (1, 40), span: Span { file_id: 0, start_byte: 39, end_byte: 41 }, text: "\\q" }
+            LexError { kind: UNTERMINATED_CHAR, start: (1, 43), end: (1, 44), span: Span { file_id: 0, start_byte: 43, end_byte: 45 }, text: "'1" }
+        "#]]
+    );
+}
+
+#[test]
+fn test_spans() {
+    let src = "fn main() {\n    \"ы\" + x1;\n}";
+    let index = span::LineIndex::new(src);
+
+    let spans: String = tokenize_file(src, 3).0.toks
+    .map(|token| {
+        let (start, end) = index.span_pos(token.span);
+        format!("{:?} {:?} {:?} {:?}\n", token.text, token.span, start, end)
+    }).collect();
+
+    expect![[r#"
+        "fn" Span { file_id: 3, start_byte: 0, end_byte: 2 } (1, 1) (1, 3)
+        "main" Span { file_id: 3, start_byte: 3, end_byte: 7 } (1, 4) (1, 8)
+        "(" Span { file_id: 3, start_byte: 7, end_byte: 8 } (1, 8) (1, 9)
+        ")" Span { file_id: 3, start_byte: 8, end_byte: 9 } (1, 9) (1, 10)
+        "{" Span { file_id: 3, start_byte: 10, end_byte: 11 } (1, 11) (1, 12)
+        "\"ы\"" Span { file_id: 3, start_byte: 16, end_byte: 20 } (2, 5) (2, 8)
+        "+" Span { file_id: 3, start_byte: 21, end_byte: 22 } (2, 9) (2, 10)
+        "x1" Span { file_id: 3, start_byte: 23, end_byte: 25 } (2, 11) (2, 13)
+        ";" Span { file_id: 3, start_byte: 25, end_byte: 26 } (2, 13) (2, 14)
+        "}" Span { file_id: 3, start_byte: 27, end_byte: 28 } (3, 1) (3, 2)
+    "#]].assert_eq(&spans);
+}
+
+#[test]
+fn test_positions() {
+    let src = "let\ta = 1;\r\n\x0Cимя\u{3000}= \"a\r\nb\";\r\n  $";
+    let (tokens, errors) = tokenize(src);
+    let eof = tokens.eof.pos;
+
+    let lexed: String = tokens.toks
+    .map(|token| {format!("{:?} {:?}\n", token.kind, token.pos)}).collect();
+
+    expect![[r#"
+        RESERVED(LET) (1, 1)
+        IDENT (1, 5)
+        EQUAL (1, 7)
+        LITERAL(INT { base: DECIMAL, suffix: None }) (1, 9)
+        SEMICOLON (1, 10)
+        IDENT (2, 2)
+        EQUAL (2, 6)
+        LITERAL(STRING) (2, 8)
+        SEMICOLON (3, 3)
+    "#]].assert_eq(&lexed);
+    expect![[r#"[LexError { kind: UNDEFINED, start: (4, 3), end: (4, 3), span: Span { file_id: 0, start_byte: 35, end_byte: 36 }, text: "$" }]"#]].assert_eq(&format!("{:?}", errors));
+
+    // EOF sits right after the last char, at the start of the next line after a newline
+    assert_eq!(eof, (4, 4));
+    assert_eq!(tokenize("fn main() {\n").0.eof.pos, (2, 1));
+}
+
+#[test]
+fn test_comments() {
+    check_lexing(
+        "//! module docs\r\n/// adds\n/// numbers\n//// plain\n// plain\nfn /* a /* nested */ b */ add /**/ /*",
+        expect![[r#"
+            INNER_DOC_COMMENT "//! module docs"
+            DOC_COMMENT "/// adds"
+            DOC_COMMENT "/// numbers"
+            RESERVED(FUNCTION)
+            IDENT "add"
+            LexError { kind: UNTERMINATED_COMMENT, start: (6, 36), end: (6, 37), span: Span { file_id: 0, start_byte: 93, end_byte: 95 }, text: "/*" }
+        "#]]
+    );
+}
+
+#[test]
+fn test_lossless() {
+    let src = "//! docs\r\nfn  main() {\t/* note */\n    let s = \"x\"; $ // done\r\n}\n";
+    let (tokens, errors) = tokenize_lossless(src, 0);
+    let tokens: Vec<Token> = tokens.toks.collect();
+
+    let restored: String = tokens.iter().map(|token| token.text).collect();
+    assert_eq!(restored, src);
+    assert_eq!(errors.len(), 1);
+
+    let lexed: String = tokens.iter()
+    .map(|token| {format!("{:?} {:?}\n", token.kind, token.text)}).collect();
+
+    expect![[r#"
+        INNER_DOC_COMMENT "//! docs"
+        NEWLINE "\r\n"
+        RESERVED(FUNCTION) "fn"
+        WHITESPACE "  "
+        IDENT "main"
+        LBRACE "("
+        RBRACE ")"
+        WHITESPACE " "
+        CLBRACE "{"
+        WHITESPACE "\t"
+        COMMENT "/* note */"
+        NEWLINE "\n"
+        WHITESPACE "    "
+        RESERVED(LET) "let"
+        WHITESPACE " "
+        IDENT "s"
+        WHITESPACE " "
+        EQUAL "="
+        WHITESPACE " "
+        LITERAL(STRING) "\"x\""
+        SEMICOLON ";"
+        WHITESPACE " "
+        UNKNOWN "$"
+        WHITESPACE " "
+        COMMENT "// done"
+        NEWLINE "\r\n"
+        CRBRACE "}"
+        NEWLINE "\n"
+    "#]].assert_eq(&lexed);
+}
+
+#[test]
+fn test_operators() {
+    check_lexing(
+        "< <= > >= == != ! && || & | ^ << >> % += -= *= /= = , : -> ",
+        expect![[r#"
+            LT
+            LTE
+            MT
+            MTE
+            DEQUAL
+            NEQUAL
+            NOT
+            AND
+            OR
+            AMPERSAND
+            PIPE
+            CARET
+            SHL
+            SHR
+            PERCENT
+            PLUS_EQUAL
+            MINUS_EQUAL
+            STAR_EQUAL
+            SLASH_EQUAL
+            EQUAL
+            COMMA
+            COLON
+            ARROW
+        "#]]
+    );
+}
+
+#[test]
+fn test_number_validation() {
+    check_lexing(
+        "10i64 3u8 1.0f32 2f64 0xFFu8 0x_ 0b102 0o19 99999999999 256u8 1.5i32 0b1f32 7q",
+        expect![[r#"
+            LITERAL(INT { base: DECIMAL, suffix: Some(I64) }) "10i64"
+            LITERAL(INT { base: DECIMAL, suffix: Some(U8) }) "3u8"
+            LITERAL(FLOAT { suffix: Some(F32) }) "1.0f32"
+            LITERAL(FLOAT { suffix: Some(F64) }) "2f64"
+            LITERAL(INT { base: HEX, suffix: Some(U8) }) "0xFFu8"
+            LITERAL(INT { base: HEX, suffix: None }) "0x_"
+            LITERAL(INT { base: BINARY, suffix: None }) "0b102"
+            LITERAL(INT { base: OCTAL, suffix: None }) "0o19"
+            LITERAL(INT { base: DECIMAL, suffix: None }) "99999999999"
+            LITERAL(INT { base: DECIMAL, suffix: Some(U8) }) "256u8"
+            LITERAL(FLOAT { suffix: None }) "1.5i32"
+            LITERAL(INT { base: BINARY, suffix: None }) "0b1f32"
+            LITERAL(INT { base: DECIMAL, suffix: None }) "7q"
+            LexError { kind: EMPTY_INT, start: (1, 30), end: (1, 32), span: Span { file_id: 0, start_byte: 29, end_byte: 32 }, text: "0x_" }
+            LexError { kind: INVALID_DIGIT, start: (1, 34), end: (1, 38), span: Span { file_id: 0, start_byte: 33, end_byte: 38 }, text: "0b102" }
+            LexError { kind: INVALID_DIGIT, start: (1, 40), end: (1, 43), span: Span { file_id: 0, start_byte: 39, end_byte: 43 }, text: "0o19" }
+            LexError { kind: INT_OVERFLOW, start: (1, 45), end: (1, 55), span: Span { file_id: 0, start_byte: 44, end_byte: 55 }, text: "99999999999" }
+            LexError { kind: INT_OVERFLOW, start: (1, 57), end: (1, 61), span: Span { file_id: 0, start_byte: 56, end_byte: 61 }, text: "256u8" }
+            LexError { kind: INVALID_SUFFIX, start: (1, 63), end: (1, 68), span: Span { file_id: 0, start_byte: 62, end_byte: 68 }, text: "1.5i32" }
+            LexError { kind: INVALID_SUFFIX, start: (1, 70), end: (1, 75), span: Span { file_id: 0, start_byte: 69, end_byte: 75 }, text: "0b1f32" }
+            LexError { kind: INVALID_SUFFIX, start: (1, 77), end: (1, 78), span: Span { file_id: 0, start_byte: 76, end_byte: 78 }, text: "7q" }
+        "#]]
+    );
+}
+
+#[test]
+fn test_raw_and_byte_strings() {
+    check_lexing(
+        r####"r"C:\path\n" r#"say "hi""# r##"a "# b"## b"bytes\n" rest"####,
+        expect![[r###"
+            LITERAL(RAW_STRING { hashes: 0 }) "r\"C:\\path\\n\""
+            LITERAL(RAW_STRING { hashes: 1 }) "r#\"say \"hi\"\"#"
+            LITERAL(RAW_STRING { hashes: 2 }) "r##\"a \"# b\"##"
+            LITERAL(BYTE_STRING) "b\"bytes\\n\""
+            IDENT "rest"
+        "###]]
+    );
+
+    let (tokens, _) = tokenize(r###"r"C:\path" r##"a "# b"## r"open"###);
+    let values: Vec<Option<&str>> = tokens.toks
+    .map(|token| match token.kind {
+        TokenKind::LITERAL(literal) => literal.raw_value(token.text),
+        _ => None
+    }).collect();
+    assert_eq!(values, [Some(r"C:\path"), Some(r##"a "# b"##), Some("open")]);
+    assert_eq!(unescape_bytes(r#"b"hi\n""#), b"hi\n");
+
+    check_lexing(
+        r###"b"caf\u{e9} é" r#x r##"never closed"#"###,
+        expect![[r##"
+            LITERAL(BYTE_STRING) "b\"caf\\u{e9} é\""
+            IDENT "x"
+            LITERAL(RAW_STRING { hashes: 2 }) "r##\"never closed\"#"
+            LexError { kind: NON_ASCII_BYTE, start: (1, 6), end: (1, 11), span: Span { file_id: 0, start_byte: 5, end_byte: 11 }, text: "\\u{e9}" }
+            LexError { kind: NON_ASCII_BYTE, start: (1, 13), end: (1, 13), span: Span { file_id: 0, start_byte: 12, end_byte: 14 }, text: "é" }
+            LexError { kind: INVALID_RAW_STRING, start: (1, 16), end: (1, 17), span: Span { file_id: 0, start_byte: 16, end_byte: 18 }, text: "r#" }
+            LexError { kind: UNTERMINATED_RAW_STRING, start: (1, 20), end: (1, 37), span: Span { file_id: 0, start_byte: 20, end_byte: 38 }, text: "r##\"never closed\"#" }
+        "##]]
+    );
+}
+
+#[test]
+fn test_ranges_and_labels() {
+    check_lexing(
+        "0..n 1..=10 'outer: for 'a' 'b 'ab'",
+        expect![[r#"
+            LITERAL(INT { base: DECIMAL, suffix: None }) "0"
+            RANGE
+            IDENT "n"
+            LITERAL(INT { base: DECIMAL, suffix: None }) "1"
+            RANGE_INCLUSIVE
+            LITERAL(INT { base: DECIMAL, suffix: None }) "10"
+            LABEL "'outer"
+            COLON
+            RESERVED(FOR)
+            LITERAL(CHAR('a')) "'a'"
+            LABEL "'b"
+            LITERAL(CHAR('a')) "'ab'"
+            LexError { kind: MULTI_CHAR, start: (1, 32), end: (1, 35), span: Span { file_id: 0, start_byte: 31, end_byte: 35 }, text: "'ab'" }
+        "#]]
+    );
+}
+
+#[test]
+fn test_symbols() {
+    let (tokens, _) = tokenize("'outer: x = outer + 1");
+    let symbols: Vec<_> = tokens.toks.map(|token| token.symbol).collect();
+
+    let outer = Some(Symbol::intern("outer"));
+    assert_eq!(symbols, vec![outer, None, Some(Symbol::intern("x")), None, outer, None, None]);
+}